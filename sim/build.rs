@@ -0,0 +1,27 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// embeds every file of assets/levels, so a new mission needs no change to the code,
+// the assets folder is shared with the game at the top of the workspace
+fn main() {
+    let levels_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("assets").join("levels");
+    println!("cargo:rerun-if-changed={}", levels_dir.display());
+
+    let mut names: Vec<String> = fs::read_dir(&levels_dir)
+        .expect("can't read assets/levels")
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+
+    let mut code = String::from("&[\n");
+    for name in names {
+        code.push_str(&format!("    (\"levels/{}\", include_bytes!({:?})),\n", name, levels_dir.join(&name)));
+    }
+    code.push_str("]\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("levels.rs");
+    fs::write(out, code).expect("can't write the embedded level list");
+}
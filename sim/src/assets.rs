@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+// folder with files that replace the embedded ones, when no --assets-dir is given
+pub const ASSETS_DIR_ENV: &str = "SPACE_INVADERS_ASSETS";
+
+// the whole levels folder, listed by build.rs, paths are relative to the "assets" folder
+pub const EMBEDDED_LEVELS: &[(&str, &[u8])] = include!(concat!(env!("OUT_DIR"), "/levels.rs"));
+
+#[derive(Debug)]
+pub enum AssetError {
+    Missing(String),
+    Corrupt(String, String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetError::Missing(path) => write!(f, "'{}' is missing", path),
+            AssetError::Corrupt(path, reason) => write!(f, "'{}' is corrupt: {}", path, reason),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+pub struct Assets {
+    override_dir: Option<PathBuf>,
+    // what the level editor saved, in the user's data folder
+    user_dir: Option<PathBuf>,
+    // compiled in next to the levels, the window adds its images and font
+    embedded: &'static [(&'static str, &'static [u8])],
+}
+
+impl Assets {
+    pub fn new(assets_dir: Option<String>) -> Self {
+        Self {
+            override_dir: assets_dir
+                .or_else(|| env::var(ASSETS_DIR_ENV).ok())
+                .map(PathBuf::from),
+            user_dir: dirs::data_dir().map(|dir| dir.join("space-invaders").join("assets")),
+            embedded: &[],
+        }
+    }
+
+    // only what's compiled in, the fallback when overridden files are broken
+    pub fn embedded() -> Self {
+        Self {
+            override_dir: None,
+            user_dir: None,
+            embedded: &[],
+        }
+    }
+
+    pub fn with_embedded(mut self, files: &'static [(&'static str, &'static [u8])]) -> Self {
+        self.embedded = files;
+        self
+    }
+
+    // where edited files are written: the override folder, or the user's one, both read back by read()
+    pub fn save_path(&self, path: &str) -> Option<PathBuf> {
+        self.override_dir.as_ref().or(self.user_dir.as_ref()).map(|dir| dir.join(path))
+    }
+
+    // the override folder wins over the user's folder, which wins over the embedded copy
+    pub fn read(&self, path: &str) -> Result<Cow<'static, [u8]>, AssetError> {
+        for dir in [&self.override_dir, &self.user_dir].into_iter().flatten() {
+            if let Ok(bytes) = fs::read(dir.join(path)) {
+                return Ok(Cow::Owned(bytes));
+            }
+        }
+
+        self.embedded
+            .iter()
+            .chain(EMBEDDED_LEVELS)
+            .find(|(name, _)| *name == path)
+            .map(|(_, bytes)| Cow::Borrowed(*bytes))
+            .ok_or_else(|| AssetError::Missing(path.to_string()))
+    }
+}
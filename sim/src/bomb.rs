@@ -0,0 +1,157 @@
+use crate::rect::Rect;
+
+const BOMB_ANIMATION_SPEED: i32 = 9;
+pub const BOMB_FRAMES: usize = 2;
+// ticks for one full swing of a wiggling bomb, a multiple of 4
+const WIGGLE_PERIOD: i32 = 40;
+// splitting bombs break up this far above the bunkers
+pub const SPLIT_ABOVE: f32 = 40.0;
+// sideways speed of the two halves
+const SPLIT_DRIFT: f32 = 70.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BombKind {
+    Rolling,
+    Squiggly,
+    Plunger,
+    Splitter,
+}
+
+pub struct BombStats {
+    // pixels per second
+    pub speed: f32,
+    // how far it swings to each side, 0.0 falls straight
+    pub wiggle: f32,
+    // bullets it takes to shoot it down
+    pub hit_points: i32,
+    pub splits: bool,
+    // points for shooting it down
+    pub score: i32,
+}
+
+// one row per kind, in the order of BombKind
+const BOMB_STATS: [BombStats; 4] = [
+    BombStats { speed: 300.0, wiggle: 0.0, hit_points: 2, splits: false, score: 20 },
+    BombStats { speed: 170.0, wiggle: 10.0, hit_points: 1, splits: false, score: 10 },
+    BombStats { speed: 480.0, wiggle: 0.0, hit_points: 1, splits: false, score: 15 },
+    BombStats { speed: 230.0, wiggle: 0.0, hit_points: 1, splits: true, score: 25 },
+];
+
+impl BombKind {
+    pub fn stats(self) -> &'static BombStats {
+        &BOMB_STATS[self as usize]
+    }
+}
+
+pub struct Bomb {
+    // set by the world, tells targets apart for piercing bullets
+    pub id: u32,
+    pub x: f32,
+    pub y: f32,
+    pub prev_x: f32,
+    pub prev_y: f32,
+    pub kind: BombKind,
+    pub hit_points: i32,
+    // where it would be without the wiggle
+    center_x: f32,
+    drift: f32,
+    // halves of a split bomb don't split again
+    half: bool,
+    age: i32,
+    update_interval: i32,
+    pub cur_frame: usize,
+    pub rect: Rect,
+    pub destroyed: bool,
+}
+
+impl Bomb {
+    pub fn new(x: f32, y: f32, kind: BombKind) -> Self {
+        Self {
+            id: 0,
+            x,
+            y,
+            prev_x: x,
+            prev_y: y,
+            kind,
+            hit_points: kind.stats().hit_points,
+            center_x: x,
+            drift: 0.0,
+            half: false,
+            age: 0,
+            update_interval: 0,
+            cur_frame: 0,
+            rect: Rect::new(x, y, 8.0, 15.0),
+            destroyed: false,
+        }
+    }
+
+    pub fn update_animation(&mut self) {
+        self.update_interval += 1;
+        if self.update_interval > BOMB_ANIMATION_SPEED {
+            self.update_interval = 0;
+            self.cur_frame += 1;
+            if self.cur_frame == BOMB_FRAMES {
+                self.cur_frame = 0;
+            }
+        }
+    }
+
+    // true when this hit was the last one it could take
+    pub fn hit(&mut self) -> bool {
+        self.hit_points -= 1;
+        if self.hit_points <= 0 {
+            self.destroyed = true;
+        }
+        self.destroyed
+    }
+
+    // the two halves once a splitting bomb falls below the given height
+    pub fn split(&mut self, height: f32) -> Option<[Bomb; 2]> {
+        if !self.kind.stats().splits || self.half || self.destroyed || self.y < height {
+            return None;
+        }
+        self.destroyed = true;
+
+        Some([-SPLIT_DRIFT, SPLIT_DRIFT].map(|drift| {
+            let mut half = Bomb::new(self.x, self.y, self.kind);
+            half.drift = drift;
+            half.half = true;
+            half
+        }))
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.update_animation();
+        self.age += 1;
+
+        let stats = self.kind.stats();
+        self.y += dt * stats.speed;
+        self.center_x += dt * self.drift;
+        self.x = self.center_x + stats.wiggle * self.swing();
+        if self.y > 500.0 {
+            self.destroyed = true;
+        }
+        self.rect.x = self.x;
+        self.rect.y = self.y;
+    }
+
+    // a triangle wave from -1.0 to 1.0 and back, in whole steps so replays
+    // come out the same on every platform, unlike sin()
+    fn swing(&self) -> f32 {
+        let quarter = WIGGLE_PERIOD / 4;
+        let phase = self.age % WIGGLE_PERIOD;
+        let steps = if phase < quarter {
+            phase
+        } else if phase < 3 * quarter {
+            2 * quarter - phase
+        } else {
+            phase - WIGGLE_PERIOD
+        };
+        steps as f32 / quarter as f32
+    }
+
+    pub fn save_position(&mut self) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use crate::rect::Rect;
+
+const BULLET_SPEED: f32 = 300.0;
+
+pub struct Bullet {
+    pub x: f32,
+    pub y: f32,
+    pub prev_x: f32,
+    pub prev_y: f32,
+    pub destroyed: bool,
+    pub rect: Rect,
+    // sideways speed, for spread shots
+    pub drift: f32,
+    // keeps flying through what it hits
+    pub piercing: bool,
+    // ids of the targets a piercing bullet already went through
+    pub hit: HashSet<u32>,
+}
+
+impl Bullet {
+    pub fn new(x:f32, y:f32) -> Self {
+        Self {
+            x,
+            y,
+            prev_x: x,
+            prev_y: y,
+            destroyed: false,
+            rect: Rect::new(x, y, 6.0, 11.0),
+            drift: 0.0,
+            piercing: false,
+            hit: HashSet::new(),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.y -= dt * BULLET_SPEED;
+        self.x += dt * self.drift;
+        if self.y < 0.0 {
+            self.destroyed = true;
+        }
+        self.rect.x = self.x;
+        self.rect.y = self.y;
+    }
+
+    // whether touching the target with this id counts as a hit, a piercing
+    // bullet hits every target once while it passes through
+    pub fn strike(&mut self, id: u32) -> bool {
+        if self.piercing {
+            self.hit.insert(id)
+        } else {
+            self.destroyed = true;
+            true
+        }
+    }
+
+    pub fn save_position(&mut self) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+}
@@ -0,0 +1,143 @@
+use crate::rect::Rect;
+
+// bunkers are masks of small square pixels that wear down where they are hit
+pub const BUNKER_PIXEL: f32 = 2.0;
+// size of the classic arch
+const ARCH_COLUMNS: usize = 55;
+const ARCH_ROWS: usize = 30;
+// the corners cut off the top of the arch, and the opening at the bottom
+const CORNER: usize = 8;
+const OPENING_RADIUS: f32 = 12.0;
+
+// what a hit blows away around the pixel it hit, '#' is cleared
+pub const BULLET_CRATER: [&str; 6] = [
+    "#..#.",
+    ".###.",
+    "#####",
+    ".###.",
+    "##.##",
+    ".#..#",
+];
+
+pub const BOMB_CRATER: [&str; 7] = [
+    "..#...#",
+    "#.####.",
+    ".######",
+    "#######",
+    ".#####.",
+    "#.###.#",
+    "..#.#..",
+];
+
+pub struct Bunker {
+    pub x: f32,
+    pub y: f32,
+    pub columns: usize,
+    pub rows: usize,
+    // row by row, true where the bunker is still standing
+    pub pixels: Vec<bool>,
+    pub rect: Rect,
+}
+
+// the classic arch, as lines of '#' for standing pixels and '.' for gaps
+pub fn arch_shape() -> Vec<String> {
+    let middle = (ARCH_COLUMNS - 1) as f32 * 0.5;
+
+    (0..ARCH_ROWS).map(|row| {
+        (0..ARCH_COLUMNS).map(|column| {
+            let cut = CORNER.saturating_sub(row);
+            let corner = column < cut || column >= ARCH_COLUMNS - cut;
+
+            let dx = (column as f32 - middle) / OPENING_RADIUS;
+            let dy = (ARCH_ROWS as f32 - row as f32) / OPENING_RADIUS;
+            let opening = dx * dx + dy * dy < 1.0;
+
+            if corner || opening { '.' } else { '#' }
+        }).collect()
+    }).collect()
+}
+
+impl Bunker {
+    // shorter lines are filled up with gaps
+    pub fn new(x: f32, y: f32, shape: &[String]) -> Self {
+        let columns = shape.iter().map(|line| line.len()).max().unwrap_or(0);
+        let rows = shape.len();
+        let mut pixels: Vec<bool> = Vec::with_capacity(columns * rows);
+
+        for line in shape {
+            let mut marks = line.bytes();
+            for _ in 0..columns {
+                pixels.push(marks.next() == Some(b'#'));
+            }
+        }
+
+        Self {
+            x,
+            y,
+            columns,
+            rows,
+            pixels,
+            rect: Rect::new(x, y, columns as f32 * BUNKER_PIXEL, rows as f32 * BUNKER_PIXEL),
+        }
+    }
+
+    pub fn is_solid(&self, column: usize, row: usize) -> bool {
+        self.pixels[row * self.columns + column]
+    }
+
+    // columns and rows of the bunker pixels under a rect, clipped to the bunker
+    fn cover(&self, rect: Rect) -> Option<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+        let overlap = self.rect.intersect(rect)?;
+        let first_column = ((overlap.x - self.x) / BUNKER_PIXEL).floor() as usize;
+        let last_column = ((overlap.right() - self.x) / BUNKER_PIXEL).ceil() as usize;
+        let first_row = ((overlap.y - self.y) / BUNKER_PIXEL).floor() as usize;
+        let last_row = ((overlap.bottom() - self.y) / BUNKER_PIXEL).ceil() as usize;
+
+        Some((first_column..last_column.min(self.columns), first_row..last_row.min(self.rows)))
+    }
+
+    // the first standing pixel under the rect, met from above for bombs or from below for bullets
+    pub fn hit(&self, rect: Rect, from_above: bool) -> Option<(usize, usize)> {
+        let (columns, rows) = self.cover(rect)?;
+        let rows: Vec<usize> = if from_above { rows.collect() } else { rows.rev().collect() };
+
+        rows.into_iter().find_map(|row| {
+            columns.clone().find(|&column| self.is_solid(column, row)).map(|column| (column, row))
+        })
+    }
+
+    // centre of a pixel in screen coordinates
+    pub fn pixel_center(&self, column: usize, row: usize) -> (f32, f32) {
+        (self.x + (column as f32 + 0.5) * BUNKER_PIXEL, self.y + (row as f32 + 0.5) * BUNKER_PIXEL)
+    }
+
+    pub fn carve(&mut self, column: usize, row: usize, crater: &[&str]) {
+        let height = crater.len() as i32;
+        for (dy, line) in crater.iter().enumerate() {
+            let width = line.len() as i32;
+            for (dx, mark) in line.bytes().enumerate() {
+                let c = column as i32 + dx as i32 - width / 2;
+                let r = row as i32 + dy as i32 - height / 2;
+                if mark == b'#' && (0..self.columns as i32).contains(&c) && (0..self.rows as i32).contains(&r) {
+                    self.pixels[r as usize * self.columns + c as usize] = false;
+                }
+            }
+        }
+    }
+
+    // invaders marching into a bunker wipe out everything they cover,
+    // true when anything was still there
+    pub fn erase(&mut self, rect: Rect) -> bool {
+        let mut erased = false;
+        if let Some((columns, rows)) = self.cover(rect) {
+            for row in rows {
+                for column in columns.clone() {
+                    let pixel = &mut self.pixels[row * self.columns + column];
+                    erased |= *pixel;
+                    *pixel = false;
+                }
+            }
+        }
+        erased
+    }
+}
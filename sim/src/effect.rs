@@ -0,0 +1,127 @@
+// how much the debris slows down every tick
+const DEBRIS_DRAG: f32 = 0.94;
+const DEBRIS_GRAVITY: f32 = 0.05;
+// the saucer's points float up a little while they are shown
+const SCORE_RISE: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EffectKind {
+    Invader,
+    Bunker,
+    Bomb,
+    Ufo,
+    Player,
+}
+
+pub struct EffectPreset {
+    pub particles: usize,
+    // pixels per tick the fastest piece starts with
+    pub speed: f32,
+    // ticks until the effect is gone
+    pub lifetime: i32,
+    pub size: f32,
+}
+
+// one row per kind, in the order of EffectKind
+const EFFECT_PRESETS: [EffectPreset; 5] = [
+    EffectPreset { particles: 14, speed: 3.0, lifetime: 30, size: 4.0 },
+    EffectPreset { particles: 4, speed: 1.5, lifetime: 15, size: 3.0 },
+    EffectPreset { particles: 6, speed: 2.0, lifetime: 18, size: 3.0 },
+    EffectPreset { particles: 20, speed: 4.0, lifetime: 90, size: 4.0 },
+    EffectPreset { particles: 28, speed: 3.5, lifetime: 100, size: 5.0 },
+];
+
+impl EffectKind {
+    pub fn preset(self) -> &'static EffectPreset {
+        &EFFECT_PRESETS[self as usize]
+    }
+}
+
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    pub prev_x: f32,
+    pub prev_y: f32,
+    vx: f32,
+    vy: f32,
+}
+
+pub struct Effect {
+    pub kind: EffectKind,
+    pub x: f32,
+    pub y: f32,
+    pub prev_y: f32,
+    pub particles: Vec<Particle>,
+    // points to show where it happened
+    pub score: Option<i32>,
+    pub age: i32,
+    pub destroyed: bool,
+}
+
+impl Effect {
+    pub fn new(kind: EffectKind, x: f32, y: f32) -> Self {
+        let preset = kind.preset();
+        let mut particles: Vec<Particle> = Vec::new();
+
+        // the debris flies out all around, the pieces don't share a speed so it looks less like a ring
+        for i in 0..preset.particles {
+            let angle = i as f32 / preset.particles as f32 * std::f32::consts::TAU;
+            let speed = preset.speed * (0.4 + 0.6 * ((i * 7) % 5) as f32 / 4.0);
+            particles.push(Particle {
+                x,
+                y,
+                prev_x: x,
+                prev_y: y,
+                vx: angle.cos() * speed,
+                vy: angle.sin() * speed,
+            });
+        }
+
+        Self {
+            kind,
+            x,
+            y,
+            prev_y: y,
+            particles,
+            score: None,
+            age: 0,
+            destroyed: false,
+        }
+    }
+
+    pub fn with_score(mut self, score: i32) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    // 1.0 when it starts, down to 0.0 when it's gone
+    pub fn fade(&self) -> f32 {
+        1.0 - self.age as f32 / self.kind.preset().lifetime as f32
+    }
+
+    pub fn update(&mut self) {
+        self.age += 1;
+        if self.age >= self.kind.preset().lifetime {
+            self.destroyed = true;
+        }
+
+        for particle in &mut self.particles {
+            particle.x += particle.vx;
+            particle.y += particle.vy;
+            particle.vx *= DEBRIS_DRAG;
+            particle.vy = particle.vy * DEBRIS_DRAG + DEBRIS_GRAVITY;
+        }
+
+        if self.score.is_some() {
+            self.y -= SCORE_RISE;
+        }
+    }
+
+    pub fn save_position(&mut self) {
+        self.prev_y = self.y;
+        for particle in &mut self.particles {
+            particle.prev_x = particle.x;
+            particle.prev_y = particle.y;
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::rect::Rect;
+
+use crate::bomb::BombKind;
+
+pub const ENEMY_FRAMES: usize = 2;
+// sprite size at scale 1.0, the hitbox leaves out the bottom of the sprite
+pub const ENEMY_WIDTH: f32 = 50.0;
+pub const ENEMY_HEIGHT: f32 = 30.0;
+pub const ENEMY_SPRITE_HEIGHT: f32 = 36.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EnemyKind {
+    Squid,
+    Crab,
+    Octopus,
+}
+
+pub struct EnemyStats {
+    // points in arcade mode, classic mode pays the same for every kind
+    pub score: i32,
+    pub hit_points: i32,
+    // the bombs it can drop, one of them is picked each time
+    pub bombs: &'static [BombKind],
+    // relative chance of being picked to drop the next bomb
+    pub bomb_frequency: u32,
+    // ticks between animation frames
+    pub animation_speed: i32,
+}
+
+// one row per kind, in the order of EnemyKind
+const ENEMY_STATS: [EnemyStats; 3] = [
+    EnemyStats { score: 30, hit_points: 1, bombs: &[BombKind::Plunger, BombKind::Splitter], bomb_frequency: 1, animation_speed: 11 },
+    EnemyStats { score: 20, hit_points: 1, bombs: &[BombKind::Squiggly], bomb_frequency: 2, animation_speed: 11 },
+    EnemyStats { score: 10, hit_points: 1, bombs: &[BombKind::Rolling], bomb_frequency: 3, animation_speed: 11 },
+];
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 3] = [EnemyKind::Squid, EnemyKind::Crab, EnemyKind::Octopus];
+
+    pub fn stats(self) -> &'static EnemyStats {
+        &ENEMY_STATS[self as usize]
+    }
+
+    // how a kind is written in level files
+    pub fn letter(self) -> char {
+        match self {
+            EnemyKind::Squid => 'S',
+            EnemyKind::Crab => 'C',
+            EnemyKind::Octopus => 'O',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'S' => Some(EnemyKind::Squid),
+            'C' => Some(EnemyKind::Crab),
+            'O' => Some(EnemyKind::Octopus),
+            _ => None,
+        }
+    }
+}
+
+pub struct Enemy {
+    // set by the world, tells targets apart for piercing bullets
+    pub id: u32,
+    pub x: f32,
+    pub y: f32,
+    pub prev_x: f32,
+    pub prev_y: f32,
+    pub kind: EnemyKind,
+    // column in the formation, counted from the left
+    pub column: usize,
+    pub hit_points: i32,
+    pub scale: f32,
+    update_interval: i32,
+    pub cur_frame: usize,
+    pub rect: Rect,
+    pub destroyed: bool,
+}
+
+impl Enemy {
+    pub fn new(x: f32, y: f32, kind: EnemyKind, scale: f32) -> Self {
+        Self {
+            id: 0,
+            x,
+            y,
+            prev_x: x,
+            prev_y: y,
+            kind,
+            column: 0,
+            hit_points: kind.stats().hit_points,
+            scale,
+            update_interval: 0,
+            cur_frame: 0,
+            rect: Rect::new(x, y, ENEMY_WIDTH * scale, ENEMY_HEIGHT * scale),
+            destroyed: false,
+        }
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = column;
+        self
+    }
+
+    pub fn next_frame(&mut self) {
+        self.cur_frame += 1;
+        if self.cur_frame == ENEMY_FRAMES {
+            self.cur_frame = 0;
+        }
+    }
+
+    pub fn update_animation(&mut self) {
+        self.update_interval += 1;
+        if self.update_interval > self.kind.stats().animation_speed {
+            self.update_interval = 0;
+            self.next_frame();
+        }
+    }
+
+    // true when this hit was the last one it could take
+    pub fn hit(&mut self) -> bool {
+        self.hit_points -= 1;
+        if self.hit_points <= 0 {
+            self.destroyed = true;
+        }
+        self.destroyed
+    }
+
+    pub fn update_rect(&mut self) {
+        self.rect.x = self.x;
+        self.rect.y = self.y;
+    }
+
+    pub fn update(&mut self) {
+        self.update_animation();
+        self.update_rect();
+    }
+
+    pub fn save_position(&mut self) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameState {
+    Intro,
+    InitLevel,
+    Game,
+    LevelFail,
+    Paused,
+    GameOver,
+    LevelCompleted,
+}
+
+// things that happened during a step, for sound and other effects to react to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameEvent {
+    PlayerShot,
+    EnemyKilled,
+    BombHitBlock,
+    PlayerDied,
+    UfoDestroyed,
+    ExtraLife,
+    MissionCompleted,
+    PowerUpCaught,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    Classic,
+    // 5x11 formation marching in steps, like the original hardware
+    Arcade,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Classic, GameMode::Arcade];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Arcade => "arcade",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        GameMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+// how hard a difficulty makes the game, as factors on what the level asks for
+pub struct DifficultyStats {
+    pub lives: i32,
+    // formation speed and its steps
+    pub speed: f32,
+    // waits between two bombs
+    pub bomb_interval: f64,
+}
+
+const DIFFICULTY_STATS: [DifficultyStats; 3] = [
+    DifficultyStats { lives: 5, speed: 0.8, bomb_interval: 1.5 },
+    DifficultyStats { lives: 3, speed: 1.0, bomb_interval: 1.0 },
+    DifficultyStats { lives: 2, speed: 1.25, bomb_interval: 0.7 },
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn stats(self) -> &'static DifficultyStats {
+        &DIFFICULTY_STATS[self as usize]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
+
+pub struct Game {
+    pub mode: GameMode,
+    // takes effect when a new game starts
+    pub difficulty: Difficulty,
+    pub score: i32,
+    pub hi_score: i32,
+    pub enemy_speed: f32,
+    pub lives: i32,
+    pub mission: i32,
+}
+
+impl Game {
+    pub fn new(mode: GameMode)  -> Self {
+        Self {
+            mode,
+            difficulty: Difficulty::Normal,
+            score: 0,
+            hi_score: 0,
+            enemy_speed: 0.0,
+            lives: 0,
+            mission: 0,
+        }
+    }
+}
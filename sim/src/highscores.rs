@@ -0,0 +1,231 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::game::GameMode;
+
+const TABLE_SIZE: usize = 10;
+const FILE_NAME: &str = "highscores.txt";
+const INITIALS_LEN: usize = 3;
+
+pub struct HighScore {
+    pub score: i32,
+    pub initials: String,
+    pub mission: i32,
+    pub date: String,
+    pub mode: String,
+}
+
+// top scores, kept in the user's data folder as one tab separated line per entry
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+    path: Option<PathBuf>,
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        let path = dirs::data_dir().map(|dir| dir.join("space-invaders").join(FILE_NAME));
+        let mut high_scores = Self {
+            entries: Vec::new(),
+            path,
+        };
+
+        if let Some(content) = high_scores.path.as_ref().and_then(|path| fs::read_to_string(path).ok()) {
+            for line in content.lines() {
+                let fields: Vec<&str> = line.split('\t').collect();
+                if let [score, initials, mission, date, mode] = fields[..] {
+                    if let (Ok(score), Ok(mission)) = (score.parse(), mission.parse()) {
+                        high_scores.insert(HighScore {
+                            score,
+                            initials: initials.to_string(),
+                            mission,
+                            date: date.to_string(),
+                            mode: mode.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        high_scores
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "no data folder")),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(&format!("{}\t{}\t{}\t{}\t{}\n",
+                entry.score, entry.initials, entry.mission, entry.date, entry.mode));
+        }
+        fs::write(path, content)
+    }
+
+    // the table is shared by the modes, each one beats its own best
+    pub fn best(&self, mode: GameMode) -> i32 {
+        self.entries.iter().find(|entry| entry.mode == mode.name()).map_or(0, |entry| entry.score)
+    }
+
+    pub fn qualifies(&self, score: i32) -> bool {
+        score > 0 && (self.entries.len() < TABLE_SIZE || score > self.entries[TABLE_SIZE - 1].score)
+    }
+
+    pub fn insert(&mut self, entry: HighScore) {
+        // older entries stay ahead of new ones with the same score
+        let pos = self.entries.iter().position(|e| e.score < entry.score).unwrap_or(self.entries.len());
+        self.entries.insert(pos, entry);
+        self.entries.truncate(TABLE_SIZE);
+    }
+}
+
+// arcade style initials: left/right change the current letter, confirm locks it
+pub struct InitialsEntry {
+    pub letters: [u8; INITIALS_LEN],
+    pub pos: usize,
+}
+
+impl Default for InitialsEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InitialsEntry {
+    pub fn new() -> Self {
+        Self {
+            letters: [b'A'; INITIALS_LEN],
+            pos: 0,
+        }
+    }
+
+    pub fn next_letter(&mut self) {
+        self.letters[self.pos] = if self.letters[self.pos] == b'Z' { b'A' } else { self.letters[self.pos] + 1 };
+    }
+
+    pub fn prev_letter(&mut self) {
+        self.letters[self.pos] = if self.letters[self.pos] == b'A' { b'Z' } else { self.letters[self.pos] - 1 };
+    }
+
+    // true once the last letter is locked
+    pub fn confirm(&mut self) -> bool {
+        self.pos += 1;
+        self.pos == INITIALS_LEN
+    }
+
+    pub fn initials(&self) -> String {
+        String::from_utf8_lossy(&self.letters).to_string()
+    }
+}
+
+// today's date as YYYY-MM-DD (UTC)
+pub fn today() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    civil_date((secs / 86400) as i64)
+}
+
+// the date from days since the unix epoch
+fn civil_date(days: i64) -> String {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: i32, initials: &str, mode: GameMode) -> HighScore {
+        HighScore {
+            score,
+            initials: initials.to_string(),
+            mission: 1,
+            date: "2024-01-01".to_string(),
+            mode: mode.name().to_string(),
+        }
+    }
+
+    fn table(entries: Vec<HighScore>) -> HighScores {
+        let mut high_scores = HighScores {
+            entries: Vec::new(),
+            path: None,
+        };
+        for entry in entries {
+            high_scores.insert(entry);
+        }
+        high_scores
+    }
+
+    fn initials(high_scores: &HighScores) -> Vec<&str> {
+        high_scores.entries.iter().map(|entry| entry.initials.as_str()).collect()
+    }
+
+    #[test]
+    fn insert_keeps_older_entries_first() {
+        let high_scores = table(vec![
+            entry(100, "AAA", GameMode::Classic),
+            entry(300, "BBB", GameMode::Classic),
+            entry(100, "CCC", GameMode::Classic),
+            entry(200, "DDD", GameMode::Classic),
+            entry(300, "EEE", GameMode::Classic),
+        ]);
+        assert_eq!(initials(&high_scores), ["BBB", "EEE", "DDD", "AAA", "CCC"]);
+    }
+
+    #[test]
+    fn full_table_needs_a_better_score() {
+        let mut high_scores = table((1..=TABLE_SIZE as i32).map(|i| entry(i * 10, "AAA", GameMode::Classic)).collect());
+        assert_eq!(high_scores.entries.len(), TABLE_SIZE);
+        assert!(!high_scores.qualifies(10));
+        assert!(high_scores.qualifies(11));
+        assert!(!high_scores.qualifies(0));
+
+        high_scores.insert(entry(10, "NEW", GameMode::Classic));
+        assert_eq!(high_scores.entries.len(), TABLE_SIZE);
+        assert!(!initials(&high_scores).contains(&"NEW"));
+
+        high_scores.insert(entry(15, "NEW", GameMode::Classic));
+        assert_eq!(high_scores.entries.len(), TABLE_SIZE);
+        assert_eq!(initials(&high_scores)[TABLE_SIZE - 1], "NEW");
+
+        assert!(table(Vec::new()).qualifies(1));
+        assert!(!table(Vec::new()).qualifies(0));
+    }
+
+    #[test]
+    fn best_is_per_mode() {
+        let high_scores = table(vec![
+            entry(900, "ARC", GameMode::Arcade),
+            entry(400, "CLA", GameMode::Classic),
+            entry(200, "CLB", GameMode::Classic),
+        ]);
+        assert_eq!(high_scores.best(GameMode::Arcade), 900);
+        assert_eq!(high_scores.best(GameMode::Classic), 400);
+        assert_eq!(table(Vec::new()).best(GameMode::Classic), 0);
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(-1), "1969-12-31");
+        assert_eq!(civil_date(59), "1970-03-01");
+        assert_eq!(civil_date(10957), "2000-01-01");
+        assert_eq!(civil_date(11016), "2000-02-29");
+        assert_eq!(civil_date(19722), "2023-12-31");
+        assert_eq!(civil_date(20744), "2026-10-18");
+    }
+}
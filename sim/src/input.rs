@@ -0,0 +1,30 @@
+// everything the simulation needs to know about the keyboard for one frame
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct InputFrame {
+    pub left: bool,
+    pub right: bool,
+    pub fire: bool,
+    pub pause: bool,
+    pub confirm: bool,
+}
+
+impl InputFrame {
+    // packs the frame into a single byte for replay files
+    pub fn to_bits(self) -> u8 {
+        (self.left as u8)
+            | (self.right as u8) << 1
+            | (self.fire as u8) << 2
+            | (self.pause as u8) << 3
+            | (self.confirm as u8) << 4
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            left: bits & 1 != 0,
+            right: bits & 1 << 1 != 0,
+            fire: bits & 1 << 2 != 0,
+            pause: bits & 1 << 3 != 0,
+            confirm: bits & 1 << 4 != 0,
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::{AssetError, Assets};
+use crate::bunker::{self, Bunker};
+use crate::enemy::{Enemy, EnemyKind};
+use crate::game::GameMode;
+
+// campaigns and the missions they list live in this folder of the assets
+const LEVELS_DIR: &str = "levels";
+
+// the player's gun when the campaign doesn't say: one shot on screen at a time,
+// and a few ticks before the next one
+const MAX_BULLETS: usize = 1;
+const FIRE_COOLDOWN: i32 = 8;
+
+#[derive(Debug)]
+pub enum LevelError {
+    Asset(AssetError),
+    Invalid(String, String),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelError::Asset(err) => write!(f, "{}", err),
+            LevelError::Invalid(path, reason) => write!(f, "'{}' is invalid: {}", path, reason),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+// for the asset error screen
+impl From<LevelError> for AssetError {
+    fn from(err: LevelError) -> Self {
+        match err {
+            LevelError::Asset(err) => err,
+            LevelError::Invalid(path, reason) => AssetError::Corrupt(path, reason),
+        }
+    }
+}
+
+// where the invaders start, one line per row: 'S' squid, 'C' crab, 'O' octopus, '.' nobody
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Formation {
+    pub left: f32,
+    pub top: f32,
+    pub column_pitch: f32,
+    pub row_pitch: f32,
+    pub scale: f32,
+    pub grid: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Speed {
+    // formation speed when the mission begins
+    pub start: f32,
+    // added every time the formation pulls down
+    pub step: f32,
+}
+
+// in seconds: at least min_interval between two bombs, plus a random wait up to max_extra
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BombRate {
+    pub min_interval: f64,
+    pub max_extra: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UfoRate {
+    // seconds between two saucers
+    pub interval: f64,
+}
+
+// the shape is drawn with '#' and '.' in bunker pixels, the classic arch when left out
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BunkerLayout {
+    pub x: f32,
+    pub y: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shape: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Level {
+    pub name: String,
+    pub formation: Formation,
+    pub speed: Speed,
+    pub bombs: BombRate,
+    pub ufo: UfoRate,
+    #[serde(default)]
+    pub bunkers: Vec<BunkerLayout>,
+}
+
+impl Level {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let level: Level = toml::from_str(text).map_err(|err| err.message().to_string())?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    pub fn load(assets: &Assets, path: &str) -> Result<Self, LevelError> {
+        Level::parse(&read_text(assets, path)?)
+            .map_err(|reason| LevelError::Invalid(path.to_string(), reason))
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let formation = &self.formation;
+        // toml takes nan and inf, which get past every comparison below
+        let numbers = [formation.left, formation.top, formation.column_pitch, formation.row_pitch, formation.scale,
+            self.speed.start, self.speed.step].map(f64::from);
+        let intervals = [self.bombs.min_interval, self.bombs.max_extra, self.ufo.interval];
+        let positions = self.bunkers.iter().flat_map(|layout| [layout.x, layout.y]).map(f64::from);
+        if !numbers.into_iter().chain(intervals).chain(positions).all(f64::is_finite) {
+            return Err("numbers must be finite, not nan or inf".to_string());
+        }
+        if self.speed.start <= 0.0 || self.speed.step < 0.0 {
+            return Err("the start speed must be above zero and the step can't be negative".to_string());
+        }
+        if formation.scale <= 0.0 || formation.column_pitch <= 0.0 || formation.row_pitch <= 0.0 {
+            return Err("formation scale and pitches must be above zero".to_string());
+        }
+        for (row, line) in formation.grid.iter().enumerate() {
+            if let Some(letter) = line.chars().find(|&c| c != '.' && EnemyKind::from_letter(c).is_none()) {
+                return Err(format!("unknown invader '{}' in row {} of the grid", letter, row + 1));
+            }
+        }
+        if !formation.grid.iter().any(|line| line.chars().any(|c| c != '.')) {
+            return Err("the grid has no invaders".to_string());
+        }
+        if self.bombs.min_interval < 0.0 || self.bombs.max_extra < 0.0 || self.ufo.interval <= 0.0 {
+            return Err("bomb and ufo intervals can't be negative".to_string());
+        }
+        for (i, layout) in self.bunkers.iter().enumerate() {
+            if layout.shape.iter().any(|line| line.chars().any(|c| c != '#' && c != '.')) {
+                return Err(format!("bunker {} has a shape with something else than '#' and '.'", i + 1));
+            }
+        }
+
+        Ok(())
+    }
+
+    // the widest row of the grid
+    pub fn columns(&self) -> usize {
+        self.formation.grid.iter().map(|line| line.chars().count()).max().unwrap_or(0)
+    }
+
+    pub fn make_enemies(&self) -> Vec<Enemy> {
+        let formation = &self.formation;
+        let mut enemies: Vec<Enemy> = Vec::new();
+
+        for (row, line) in formation.grid.iter().enumerate() {
+            for (column, letter) in line.chars().enumerate() {
+                if let Some(kind) = EnemyKind::from_letter(letter) {
+                    enemies.push(
+                        Enemy::new(
+                            formation.left + column as f32 * formation.column_pitch,
+                            formation.top + row as f32 * formation.row_pitch,
+                            kind,
+                            formation.scale,
+                        ).with_column(column),
+                    );
+                }
+            }
+        }
+
+        enemies
+    }
+
+    pub fn make_bunkers(&self) -> Vec<Bunker> {
+        self.bunkers.iter().map(|layout| {
+            if layout.shape.is_empty() {
+                Bunker::new(layout.x, layout.y, &bunker::arch_shape())
+            } else {
+                Bunker::new(layout.x, layout.y, &layout.shape)
+            }
+        }).collect()
+    }
+}
+
+// the player's gun for every mission of a campaign, power-ups change it for a while
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct Gun {
+    // player bullets allowed on screen together
+    pub max_bullets: usize,
+    // ticks between two shots
+    pub fire_cooldown: i32,
+}
+
+impl Default for Gun {
+    fn default() -> Self {
+        Self {
+            max_bullets: MAX_BULLETS,
+            fire_cooldown: FIRE_COOLDOWN,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CampaignFile {
+    levels: Vec<String>,
+    #[serde(default)]
+    repeat_speedup: f32,
+    #[serde(default)]
+    gun: Gun,
+}
+
+// the missions of a game mode, in the order they are played
+#[derive(Clone)]
+pub struct Campaign {
+    pub levels: Vec<Level>,
+    // once the list runs out the last mission repeats, this much faster every time
+    pub repeat_speedup: f32,
+    pub gun: Gun,
+}
+
+impl Campaign {
+    pub fn path(mode: GameMode) -> String {
+        format!("{}/{}.toml", LEVELS_DIR, mode.name())
+    }
+
+    pub fn load(assets: &Assets, mode: GameMode) -> Result<Self, LevelError> {
+        let path = Campaign::path(mode);
+        let file: CampaignFile = toml::from_str(&read_text(assets, &path)?)
+            .map_err(|err| LevelError::Invalid(path.clone(), err.message().to_string()))?;
+        if file.levels.is_empty() {
+            return Err(LevelError::Invalid(path, "no levels listed".to_string()));
+        }
+        if !file.repeat_speedup.is_finite() || file.repeat_speedup < 0.0 {
+            return Err(LevelError::Invalid(path, "repeat_speedup must be a number that isn't negative".to_string()));
+        }
+        if file.gun.max_bullets == 0 || file.gun.fire_cooldown < 0 {
+            return Err(LevelError::Invalid(path, "the gun needs a bullet and a cooldown that isn't negative".to_string()));
+        }
+
+        let mut levels: Vec<Level> = Vec::new();
+        for name in &file.levels {
+            levels.push(Level::load(assets, &Campaign::level_path(name))?);
+        }
+
+        Ok(Self {
+            levels,
+            repeat_speedup: file.repeat_speedup,
+            gun: file.gun,
+        })
+    }
+
+    pub fn level_path(name: &str) -> String {
+        format!("{}/{}", LEVELS_DIR, name)
+    }
+
+    // a campaign of a single level, which repeats forever
+    pub fn single(level: Level) -> Self {
+        Self {
+            levels: vec![level],
+            repeat_speedup: 0.0,
+            gun: Gun::default(),
+        }
+    }
+
+    pub fn with_gun(mut self, gun: Gun) -> Self {
+        self.gun = gun;
+        self
+    }
+
+    pub fn level(&self, mission: i32) -> &Level {
+        let index = (mission.max(1) as usize - 1).min(self.levels.len() - 1);
+        &self.levels[index]
+    }
+
+    // FNV-1a of everything the game reads from the campaign, the same on every build and platform
+    pub fn fingerprint(&self) -> u64 {
+        let mut data: Vec<u8> = Vec::new();
+        for level in &self.levels {
+            data.extend_from_slice(level.to_toml().as_bytes());
+        }
+        data.extend_from_slice(&self.repeat_speedup.to_le_bytes());
+        data.extend_from_slice(&(self.gun.max_bullets as u64).to_le_bytes());
+        data.extend_from_slice(&self.gun.fire_cooldown.to_le_bytes());
+
+        data.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+    }
+
+    // formation speed at the start of a mission
+    pub fn start_speed(&self, mission: i32) -> f32 {
+        let repeats = (mission.max(1) as usize).saturating_sub(self.levels.len());
+        self.level(mission).speed.start + repeats as f32 * self.repeat_speedup
+    }
+}
+
+fn read_text(assets: &Assets, path: &str) -> Result<String, LevelError> {
+    let bytes = assets.read(path).map_err(LevelError::Asset)?;
+    String::from_utf8(bytes.into_owned())
+        .map_err(|_| LevelError::Asset(AssetError::Corrupt(path.to_string(), "not UTF-8 text".to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEVEL: &str = r#"
+name = "Test"
+
+[formation]
+left = 92.0
+top = 80.0
+column_pitch = 48.0
+row_pitch = 36.0
+scale = 0.72
+grid = ["S.S", "COC"]
+
+[speed]
+start = 0.5
+step = 0.1
+
+[bombs]
+min_interval = 0.6
+max_extra = 4.0
+
+[ufo]
+interval = 7.0
+
+[[bunkers]]
+x = 100.0
+y = 400.0
+"#;
+
+    // the test level with one value replaced, `key = value` must be in it exactly once
+    fn level_with(from: &str, to: &str) -> String {
+        assert_eq!(LEVEL.matches(from).count(), 1, "{}", from);
+        LEVEL.replace(from, to)
+    }
+
+    // an override folder holding only the given campaign file for classic mode
+    fn campaign_in(name: &str, text: &str) -> Result<Campaign, LevelError> {
+        let dir = std::env::temp_dir().join(format!("space-invaders-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(dir.join(LEVELS_DIR)).unwrap();
+        std::fs::write(dir.join(Campaign::path(GameMode::Classic)), text).unwrap();
+        let campaign = Campaign::load(&Assets::new(Some(dir.to_string_lossy().into_owned())), GameMode::Classic);
+        std::fs::remove_dir_all(&dir).unwrap();
+        campaign
+    }
+
+    #[test]
+    fn level_parses() {
+        let level = Level::parse(LEVEL).unwrap();
+        assert_eq!(level.name, "Test");
+        assert_eq!(level.columns(), 3);
+        assert_eq!(level.make_enemies().len(), 5);
+        assert_eq!(level.make_bunkers().len(), 1);
+        assert_eq!(Level::parse(&level.to_toml()).unwrap().to_toml(), level.to_toml());
+    }
+
+    #[test]
+    fn broken_levels_are_rejected() {
+        let broken = [
+            level_with("start = 0.5", "start = 0.0"),
+            level_with("start = 0.5", "start = -1.0"),
+            level_with("start = 0.5", "start = nan"),
+            level_with("step = 0.1", "step = -0.1"),
+            level_with("step = 0.1", "step = inf"),
+            level_with("left = 92.0", "left = -inf"),
+            level_with("x = 100.0", "x = nan"),
+            level_with("interval = 7.0", "interval = 0.0"),
+            level_with("max_extra = 4.0", "max_extra = nan"),
+            level_with("scale = 0.72", "scale = 0.0"),
+            level_with(r#"grid = ["S.S", "COC"]"#, r#"grid = ["...", "..."]"#),
+            level_with(r#"grid = ["S.S", "COC"]"#, r#"grid = ["SXS"]"#),
+            level_with("y = 400.0", "y = 400.0\nshape = [\"#x#\"]"),
+            level_with("name = \"Test\"", "name = \"Test\"\nlives = 3"),
+        ];
+
+        for text in broken {
+            assert!(Level::parse(&text).is_err(), "accepted:\n{}", text);
+        }
+    }
+
+    #[test]
+    fn campaign_needs_levels_and_a_gun() {
+        let ok = campaign_in("ok", "levels = [\"classic_1.toml\"]\n[gun]\nmax_bullets = 3\n").unwrap();
+        assert_eq!(ok.levels.len(), 1);
+        assert_eq!(ok.gun.max_bullets, 3);
+        assert_eq!(ok.gun.fire_cooldown, FIRE_COOLDOWN);
+
+        let broken = [
+            ("empty", "levels = []"),
+            ("no-bullets", "levels = [\"classic_1.toml\"]\n[gun]\nmax_bullets = 0\n"),
+            ("cooldown", "levels = [\"classic_1.toml\"]\n[gun]\nfire_cooldown = -1\n"),
+            ("gun-field", "levels = [\"classic_1.toml\"]\n[gun]\nammo = 3\n"),
+            ("speedup", "levels = [\"classic_1.toml\"]\nrepeat_speedup = -0.5\n"),
+            ("missing", "levels = [\"classic_9.toml\"]"),
+        ];
+        for (name, text) in broken {
+            assert!(campaign_in(name, text).is_err(), "accepted {}", name);
+        }
+    }
+
+    #[test]
+    fn last_level_repeats_faster() {
+        let first = Level::parse(LEVEL).unwrap();
+        let second = Level::parse(&level_with("start = 0.5", "start = 1.0")).unwrap();
+        let campaign = Campaign { levels: vec![first, second], repeat_speedup: 0.25, gun: Gun::default() };
+
+        assert_eq!(campaign.start_speed(0), 0.5);
+        assert_eq!(campaign.start_speed(1), 0.5);
+        assert_eq!(campaign.start_speed(2), 1.0);
+        assert_eq!(campaign.start_speed(3), 1.25);
+        assert_eq!(campaign.start_speed(5), 1.75);
+        assert_eq!(campaign.level(5).speed.start, 1.0);
+    }
+}
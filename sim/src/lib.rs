@@ -0,0 +1,21 @@
+// the game without a window: the simulation and its level files, replays,
+// high scores and the sound synthesizer, so runs can be played and tested
+// on machines without a GPU or a sound card
+
+pub mod rect;
+pub mod assets;
+pub mod synth;
+pub mod input;
+pub mod game;
+pub mod level;
+pub mod replay;
+pub mod world;
+pub mod highscores;
+pub mod player;
+pub mod enemy;
+pub mod bunker;
+pub mod bullet;
+pub mod bomb;
+pub mod ufo;
+pub mod effect;
+pub mod powerup;
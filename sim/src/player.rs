@@ -0,0 +1,88 @@
+use crate::rect::Rect;
+
+use crate::input::InputFrame;
+use crate::world::FIELD_WIDTH;
+
+pub const MOVE_STEP: f32 = 4.0;
+pub const PLAYER_WIDTH: f32 = 70.0;
+pub const PLAYER_HEIGHT: f32 = 30.0;
+const START_X: f32 = 320.0;
+// bombs pass through a freshly respawned ship for this many ticks, while it blinks
+const INVULNERABLE_TICKS: i32 = 120;
+const BLINK_TICKS: i32 = 6;
+
+pub struct Player {
+    pub x: f32,
+    pub y: f32,
+    pub prev_x: f32,
+    pub prev_y: f32,
+    pub rect: Rect,
+    pub invulnerable: i32,
+    // takes the next bomb instead of the ship
+    pub shield: bool,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Player {
+    pub fn new() -> Self {
+        Self {
+            x: START_X,
+            y: 480.0,
+            prev_x: START_X,
+            prev_y: 480.0,
+            rect: Rect::new(0.0, 0.0, 0.0, 0.0),
+            invulnerable: 0,
+            shield: false,
+        }
+    }
+
+    // back at the centre with a moment of safety
+    pub fn respawn(&mut self) {
+        self.x = START_X;
+        self.prev_x = START_X;
+        self.invulnerable = INVULNERABLE_TICKS;
+        self.update_rect();
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable > 0
+    }
+
+    // hidden every other blink while invulnerable
+    pub fn visible(&self) -> bool {
+        self.invulnerable / BLINK_TICKS % 2 == 0
+    }
+
+    fn update_rect(&mut self) {
+        self.rect.w = PLAYER_WIDTH;
+        self.rect.h = PLAYER_HEIGHT;
+        self.rect.x = self.x;
+        self.rect.y = self.y;
+    }
+
+    pub fn update(&mut self, input: &InputFrame) {
+        if input.left && self.x > 0.0 {
+            self.x -= MOVE_STEP;
+        }
+
+        if input.right && self.x < FIELD_WIDTH - PLAYER_WIDTH {
+            self.x += MOVE_STEP;
+        }
+
+        if self.invulnerable > 0 {
+            self.invulnerable -= 1;
+        }
+
+        self.update_rect();
+    }
+
+    pub fn save_position(&mut self) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+}
@@ -0,0 +1,92 @@
+use crate::rect::Rect;
+
+const CAPSULE_SPEED: f32 = 120.0;
+pub const CAPSULE_SIZE: f32 = 20.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUpKind {
+    RapidFire,
+    SpreadShot,
+    Piercing,
+    Shield,
+    BunkerRepair,
+    ExtraLife,
+}
+
+pub struct PowerUpInfo {
+    pub name: &'static str,
+    // printed on the capsule
+    pub letter: &'static str,
+    // ticks the effect lasts, 0 for the ones that act once when caught
+    pub duration: i32,
+}
+
+// one row per kind, in the order of PowerUpKind
+const POWER_UPS: [PowerUpInfo; 6] = [
+    PowerUpInfo { name: "RAPID FIRE", letter: "R", duration: 600 },
+    PowerUpInfo { name: "SPREAD SHOT", letter: "S", duration: 600 },
+    PowerUpInfo { name: "PIERCING", letter: "P", duration: 480 },
+    PowerUpInfo { name: "SHIELD", letter: "H", duration: 0 },
+    PowerUpInfo { name: "REPAIR", letter: "B", duration: 0 },
+    PowerUpInfo { name: "EXTRA LIFE", letter: "L", duration: 0 },
+];
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 6] = [
+        PowerUpKind::RapidFire,
+        PowerUpKind::SpreadShot,
+        PowerUpKind::Piercing,
+        PowerUpKind::Shield,
+        PowerUpKind::BunkerRepair,
+        PowerUpKind::ExtraLife,
+    ];
+
+    pub fn info(self) -> &'static PowerUpInfo {
+        &POWER_UPS[self as usize]
+    }
+}
+
+// the timed power-up the player's gun is running on
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    pub ticks_left: i32,
+}
+
+// what a destroyed saucer sometimes leaves behind
+pub struct Capsule {
+    pub x: f32,
+    pub y: f32,
+    pub prev_x: f32,
+    pub prev_y: f32,
+    pub kind: PowerUpKind,
+    pub rect: Rect,
+    pub destroyed: bool,
+}
+
+impl Capsule {
+    pub fn new(x: f32, y: f32, kind: PowerUpKind) -> Self {
+        Self {
+            x,
+            y,
+            prev_x: x,
+            prev_y: y,
+            kind,
+            rect: Rect::new(x, y, CAPSULE_SIZE, CAPSULE_SIZE),
+            destroyed: false,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.y += dt * CAPSULE_SPEED;
+        if self.y > 500.0 {
+            self.destroyed = true;
+        }
+        self.rect.x = self.x;
+        self.rect.y = self.y;
+    }
+
+    pub fn save_position(&mut self) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+}
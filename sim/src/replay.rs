@@ -0,0 +1,191 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+use crate::game::{Difficulty, GameMode};
+use crate::input::InputFrame;
+
+// replay file layout (little endian):
+//   magic "SIRP", version byte, seed u64, game mode byte, difficulty byte,
+//   campaign fingerprint u64,
+//   then one record per frame: frame duration f32 + input bits u8
+const MAGIC: &[u8; 4] = b"SIRP";
+const HEADER_LEN: usize = 23;
+// bumped with every change to the game rules, older replays would play out differently
+const VERSION: u8 = 6;
+
+pub struct Recorder {
+    file: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &str, seed: u64, mode: GameMode, difficulty: Difficulty, campaign: u64) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&[VERSION])?;
+        file.write_all(&seed.to_le_bytes())?;
+        file.write_all(&[mode_to_byte(mode)])?;
+        file.write_all(&[difficulty as u8])?;
+        file.write_all(&campaign.to_le_bytes())?;
+        file.flush()?;
+
+        Ok(Self {
+            file,
+        })
+    }
+
+    // flushed every frame, the game can be closed at any moment
+    pub fn record(&mut self, dt: f32, input: InputFrame) -> io::Result<()> {
+        self.file.write_all(&dt.to_le_bytes())?;
+        self.file.write_all(&[input.to_bits()])?;
+        self.file.flush()
+    }
+}
+
+fn mode_to_byte(mode: GameMode) -> u8 {
+    GameMode::ALL.iter().position(|m| *m == mode).unwrap() as u8
+}
+
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    // fingerprint of the campaign it was recorded with
+    pub campaign: u64,
+    pub frames: Vec<(f32, InputFrame)>,
+}
+
+impl Replay {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let mut data: Vec<u8> = Vec::new();
+        BufReader::new(File::open(path)?).read_to_end(&mut data)?;
+
+        if data.len() < 5 || &data[0..4] != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a replay file"));
+        }
+        if data[4] != VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                format!("recorded by another version of the game (replay version {}, this one plays {})", data[4], VERSION)));
+        }
+        if data.len() < HEADER_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a replay file"));
+        }
+
+        let seed = u64::from_le_bytes(data[5..13].try_into().unwrap());
+        let mode = *GameMode::ALL.get(data[13] as usize)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unknown game mode"))?;
+        let difficulty = *Difficulty::ALL.get(data[14] as usize)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unknown difficulty"))?;
+        let campaign = u64::from_le_bytes(data[15..23].try_into().unwrap());
+        let frames = data[HEADER_LEN..]
+            .chunks_exact(5)
+            .map(|record| {
+                let dt = f32::from_le_bytes(record[0..4].try_into().unwrap());
+                (dt, InputFrame::from_bits(record[4]))
+            })
+            .collect();
+
+        Ok(Self {
+            seed,
+            mode,
+            difficulty,
+            campaign,
+            frames,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a file of its own in the temp folder for every test, they run in parallel
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("space-invaders-{}-{}.rep", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn header(version: u8, mode: u8, difficulty: u8) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.push(version);
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.push(mode);
+        data.push(difficulty);
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data
+    }
+
+    fn load_bytes(name: &str, data: &[u8]) -> io::Result<Replay> {
+        let path = temp_path(name);
+        std::fs::write(&path, data).unwrap();
+        let replay = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        replay
+    }
+
+    fn assert_rejected(name: &str, data: &[u8], reason: &str) {
+        match load_bytes(name, data) {
+            Ok(_) => panic!("{} was accepted", name),
+            Err(err) => {
+                assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+                assert!(err.to_string().contains(reason), "{}: {}", name, err);
+            },
+        }
+    }
+
+    #[test]
+    fn input_bits_round_trip() {
+        for bits in 0..32 {
+            assert_eq!(InputFrame::from_bits(bits).to_bits(), bits);
+        }
+
+        let input = InputFrame { left: true, fire: true, confirm: true, ..InputFrame::default() };
+        assert_eq!(input.to_bits(), 0b10101);
+        assert_eq!(InputFrame::from_bits(input.to_bits()), input);
+    }
+
+    #[test]
+    fn recorded_replay_loads_back() {
+        let path = temp_path("round-trip");
+        let frames = [
+            (1.0 / 60.0, InputFrame::default()),
+            (0.02, InputFrame { right: true, fire: true, ..InputFrame::default() }),
+            (0.25, InputFrame { pause: true, confirm: true, ..InputFrame::default() }),
+        ];
+
+        let mut recorder = Recorder::create(&path, 12345, GameMode::Arcade, Difficulty::Hard, 0xdead_beef).unwrap();
+        for (dt, input) in frames {
+            recorder.record(dt, input).unwrap();
+        }
+        drop(recorder);
+
+        let replay = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replay.seed, 12345);
+        assert_eq!(replay.mode, GameMode::Arcade);
+        assert_eq!(replay.difficulty, Difficulty::Hard);
+        assert_eq!(replay.campaign, 0xdead_beef);
+        assert_eq!(replay.frames, frames);
+    }
+
+    #[test]
+    fn header_only_is_an_empty_replay() {
+        let replay = load_bytes("empty", &header(VERSION, 0, 1)).unwrap();
+        assert_eq!(replay.mode, GameMode::Classic);
+        assert_eq!(replay.difficulty, Difficulty::Normal);
+        assert!(replay.frames.is_empty());
+    }
+
+    #[test]
+    fn broken_headers_are_rejected() {
+        let mut wrong_magic = header(VERSION, 0, 0);
+        wrong_magic[0] = b'X';
+        assert_rejected("magic", &wrong_magic, "not a replay file");
+        assert_rejected("version", &header(VERSION - 1, 0, 0), "another version");
+        assert_rejected("short", &header(VERSION, 0, 0)[..HEADER_LEN - 1], "not a replay file");
+        assert_rejected("tiny", b"SIR", "not a replay file");
+        assert_rejected("mode", &header(VERSION, GameMode::ALL.len() as u8, 0), "unknown game mode");
+        assert_rejected("difficulty", &header(VERSION, 0, Difficulty::ALL.len() as u8), "unknown difficulty");
+    }
+}
@@ -0,0 +1,292 @@
+// tiny chip sound generator: square and noise oscillators with an envelope,
+// an exponential pitch sweep and vibrato, rendered into mono PCM
+
+pub const SAMPLE_RATE: u32 = 22050;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Waveform {
+    // duty cycle, the part of the period spent high (0.5 is a plain square)
+    Square(f32),
+    // pseudo random bits from a 15 bit shift register, changing at the oscillator frequency
+    Noise,
+}
+
+// times in seconds, sustain as a level between 0.0 and 1.0
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Envelope {
+    // full level from the first to the last sample, for seamless loops
+    pub const FLAT: Envelope = Envelope { attack: 0.0, decay: 0.0, sustain: 1.0, release: 0.0 };
+
+    pub fn level(&self, t: f32, duration: f32) -> f32 {
+        let level = if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (t - self.attack) / self.decay
+        } else {
+            self.sustain
+        };
+
+        // measured from the end, so the last instant is exactly silent
+        if t > duration - self.release && self.release > 0.0 {
+            level * ((duration - t) / self.release).clamp(0.0, 1.0)
+        } else {
+            level
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Voice {
+    pub waveform: Waveform,
+    // the pitch slides from start to end over the whole duration
+    pub start_freq: f32,
+    pub end_freq: f32,
+    pub vibrato_rate: f32,
+    pub vibrato_depth: f32,
+    pub envelope: Envelope,
+    pub duration: f32,
+    pub volume: f32,
+}
+
+impl Voice {
+    pub fn render(&self) -> Vec<f32> {
+        let count = (self.duration * SAMPLE_RATE as f32) as usize;
+        let mut samples: Vec<f32> = Vec::with_capacity(count);
+
+        let mut phase: f32 = 0.0;
+        let mut lfsr: u16 = 1;
+        let mut noise_bit: f32 = 1.0;
+
+        for i in 0..count {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let sweep = self.start_freq * (self.end_freq / self.start_freq).powf(t / self.duration);
+            let freq = sweep + self.vibrato_depth * (std::f32::consts::TAU * self.vibrato_rate * t).sin();
+
+            phase += freq.max(0.0) / SAMPLE_RATE as f32;
+            let value = match self.waveform {
+                Waveform::Square(duty) => if phase.fract() < duty { 1.0 } else { -1.0 },
+                Waveform::Noise => {
+                    while phase >= 1.0 {
+                        phase -= 1.0;
+                        let feedback = (lfsr ^ (lfsr >> 1)) & 1;
+                        lfsr = (lfsr >> 1) | (feedback << 14);
+                        noise_bit = if lfsr & 1 == 1 { 1.0 } else { -1.0 };
+                    }
+                    noise_bit
+                },
+            };
+
+            samples.push(value * self.envelope.level(t, self.duration) * self.volume);
+        }
+
+        samples
+    }
+}
+
+pub fn laser() -> Voice {
+    Voice {
+        waveform: Waveform::Square(0.25),
+        start_freq: 1400.0,
+        end_freq: 300.0,
+        vibrato_rate: 0.0,
+        vibrato_depth: 0.0,
+        envelope: Envelope { attack: 0.002, decay: 0.05, sustain: 0.6, release: 0.08 },
+        duration: 0.18,
+        volume: 0.35,
+    }
+}
+
+// the length sets how big the blast sounds
+pub fn explosion(duration: f32) -> Voice {
+    Voice {
+        waveform: Waveform::Noise,
+        start_freq: 4000.0,
+        end_freq: 300.0,
+        vibrato_rate: 0.0,
+        vibrato_depth: 0.0,
+        envelope: Envelope { attack: 0.002, decay: duration * 0.3, sustain: 0.5, release: duration * 0.6 },
+        duration,
+        volume: 0.5,
+    }
+}
+
+// loops without a click: flat envelope and a whole number of vibrato periods
+pub fn ufo_warble() -> Voice {
+    Voice {
+        waveform: Waveform::Square(0.5),
+        start_freq: 650.0,
+        end_freq: 650.0,
+        vibrato_rate: 8.0,
+        vibrato_depth: 180.0,
+        envelope: Envelope::FLAT,
+        duration: 0.25,
+        volume: 0.2,
+    }
+}
+
+pub fn ufo_hit() -> Voice {
+    Voice {
+        waveform: Waveform::Square(0.5),
+        start_freq: 1600.0,
+        end_freq: 150.0,
+        vibrato_rate: 20.0,
+        vibrato_depth: 120.0,
+        envelope: Envelope { attack: 0.002, decay: 0.1, sustain: 0.7, release: 0.3 },
+        duration: 0.6,
+        volume: 0.3,
+    }
+}
+
+// rising jingle, longer for bigger rewards
+pub fn fanfare(duration: f32) -> Voice {
+    Voice {
+        waveform: Waveform::Square(0.5),
+        start_freq: 300.0,
+        end_freq: 1500.0,
+        vibrato_rate: 12.0,
+        vibrato_depth: 40.0,
+        envelope: Envelope { attack: 0.01, decay: 0.05, sustain: 0.8, release: duration * 0.4 },
+        duration,
+        volume: 0.3,
+    }
+}
+
+// the four descending bass notes of the march
+pub fn march_note(note: usize) -> Voice {
+    const NOTES: [f32; 4] = [98.0, 87.3, 77.8, 73.4];
+    let freq = NOTES[note % NOTES.len()];
+
+    Voice {
+        waveform: Waveform::Square(0.5),
+        start_freq: freq,
+        end_freq: freq * 0.97,
+        vibrato_rate: 0.0,
+        vibrato_depth: 0.0,
+        envelope: Envelope { attack: 0.003, decay: 0.03, sustain: 0.7, release: 0.07 },
+        duration: 0.12,
+        volume: 0.6,
+    }
+}
+
+// 16 bit mono WAV, the format the sound loader understands
+pub fn to_wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav: Vec<u8> = Vec::with_capacity(44 + data_len as usize);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(start_freq: f32, end_freq: f32, envelope: Envelope) -> Voice {
+        Voice {
+            waveform: Waveform::Square(0.5),
+            start_freq,
+            end_freq,
+            vibrato_rate: 0.0,
+            vibrato_depth: 0.0,
+            envelope,
+            duration: 0.5,
+            volume: 0.8,
+        }
+    }
+
+    // sign changes of a square wave, two per period
+    fn crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn render_length_follows_duration() {
+        for voice in [laser(), explosion(0.3), ufo_warble(), ufo_hit(), fanfare(0.9), march_note(2)] {
+            let samples = voice.render();
+            assert_eq!(samples.len(), (voice.duration * SAMPLE_RATE as f32) as usize);
+        }
+    }
+
+    #[test]
+    fn render_stays_within_volume() {
+        for voice in [laser(), explosion(1.0), ufo_warble(), ufo_hit(), fanfare(0.4), march_note(0)] {
+            assert!(voice.render().iter().all(|s| s.is_finite() && s.abs() <= voice.volume + f32::EPSILON));
+        }
+    }
+
+    #[test]
+    fn envelope_is_silent_after_release() {
+        let envelope = Envelope { attack: 0.01, decay: 0.05, sustain: 0.6, release: 0.2 };
+        let voice = tone(440.0, 440.0, envelope);
+        assert_eq!(envelope.level(voice.duration, voice.duration), 0.0);
+
+        // the last sample is one sample period short of the end of the release
+        let samples = voice.render();
+        let step = voice.volume / (envelope.release * SAMPLE_RATE as f32);
+        assert!(samples.last().unwrap().abs() <= step * 1.01);
+        assert!(samples.iter().any(|s| s.abs() > 0.5 * voice.volume));
+    }
+
+    #[test]
+    fn sweep_changes_square_period() {
+        let samples = tone(2000.0, 250.0, Envelope::FLAT).render();
+        let window = SAMPLE_RATE as usize / 10;
+        let early = crossings(&samples[..window]);
+        let late = crossings(&samples[samples.len() - window..]);
+        assert!(early > late * 4, "early {} late {}", early, late);
+
+        let steady = tone(500.0, 500.0, Envelope::FLAT).render();
+        assert_eq!(crossings(&steady[..window]) / 10, crossings(&steady[steady.len() - window..]) / 10);
+    }
+
+    #[test]
+    fn wav_header_and_data() {
+        let samples = [0.0, 1.0, -1.0, 0.5, 2.0];
+        let wav = to_wav(&samples);
+        let u16_at = |i: usize| u16::from_le_bytes([wav[i], wav[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes(wav[i..i + 4].try_into().unwrap());
+
+        assert_eq!(wav.len(), 44 + samples.len() * 2);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(4) as usize, wav.len() - 8);
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(&wav[12..16], b"fmt ");
+        assert_eq!(u32_at(16), 16);
+        assert_eq!(u16_at(20), 1);
+        assert_eq!(u16_at(22), 1);
+        assert_eq!(u32_at(24), SAMPLE_RATE);
+        assert_eq!(u32_at(28), SAMPLE_RATE * 2);
+        assert_eq!(u16_at(32), 2);
+        assert_eq!(u16_at(34), 16);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(40) as usize, samples.len() * 2);
+
+        // clamped to the 16 bit range
+        assert_eq!(u16_at(46) as i16, i16::MAX);
+        assert_eq!(u16_at(48) as i16, -i16::MAX);
+        assert_eq!(u16_at(52) as i16, i16::MAX);
+    }
+}
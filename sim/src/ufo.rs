@@ -1,41 +1,47 @@
-use macroquad::prelude::*;
+use crate::rect::Rect;
+
+use crate::world::FIELD_WIDTH;
 
 const UFO_ANIMATION_SPEED: i32 = 7;
 const UFO_FLIGHT_SPEED: f32 = 3.0;
+pub const UFO_FRAMES: usize = 5;
+pub const UFO_WIDTH: f32 = 100.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Left,
+    Right,
+}
 
 pub struct Ufo {
     pub x: f32,
     pub y: f32,
-    texture: Vec<Texture2D>,
+    pub prev_x: f32,
+    pub prev_y: f32,
     update_interval: i32,
-    cur_frame: usize,
+    pub cur_frame: usize,
     pub rect: Rect,
     pub destroyed: bool,
-    pub side: String,
+    // where it comes from
+    pub side: Side,
 }
 
 impl Ufo {
-    pub async fn new(from_side: &str) -> Self {
-        let mut sprites:Vec<Texture2D> = Vec::new();
-
-        for i in 0..=4 {
-            let path = format!("assets/images/ufo/ufo_{}.png", i);
-            sprites.push(load_texture(&path).await.unwrap());
-        }
-
-        let x = match from_side {
-            "left" => -200.0,
-            _ => 700.0,
+    pub fn new(side: Side) -> Self {
+        let x = match side {
+            Side::Left => -200.0,
+            Side::Right => FIELD_WIDTH,
         };
         
         Self {
             x,
             y: 10.0,
-            side: from_side.to_string(),
-            texture: sprites,
+            prev_x: x,
+            prev_y: 10.0,
+            side,
             update_interval: 0,
             cur_frame: 0,
-            rect: Rect::new(x, 10.0, 100.0, 20.0),
+            rect: Rect::new(x, 10.0, UFO_WIDTH, 20.0),
             destroyed: false,
         }
     }
@@ -45,35 +51,36 @@ impl Ufo {
         if self.update_interval > UFO_ANIMATION_SPEED {
             self.update_interval = 0;
             self.cur_frame += 1;
-            if self.cur_frame == self.texture.len() {
+            if self.cur_frame == UFO_FRAMES {
                 self.cur_frame = 0;
             }
         }
     }
 
     pub fn update(&mut self) {
-        match self.side.to_string().as_str() {
-            "right" => {
+        self.update_animation();
+
+        match self.side {
+            Side::Right => {
                 self.x -= UFO_FLIGHT_SPEED;
-                if self.x < 0.0 - self.texture[self.cur_frame].width() {
+                if self.x < 0.0 - UFO_WIDTH {
                     self.destroyed = true;
                 }
             },
-            _ => {
+            Side::Left => {
                 self.x += UFO_FLIGHT_SPEED;
-                if self.x > 700.0 {
+                if self.x > FIELD_WIDTH {
                     self.destroyed = true;
                 }
             }
         }
-    }
-
-    pub fn draw(&mut self) {
-        self.update_animation();
-        self.update();
-        draw_texture(self.texture[self.cur_frame], self.x, self.y, WHITE);
 
         self.rect.x = self.x;
         self.rect.y = self.y;
     }
+
+    pub fn save_position(&mut self) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
 }
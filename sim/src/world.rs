@@ -0,0 +1,858 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::{Difficulty, Game, GameEvent, GameMode, GameState};
+use crate::input::InputFrame;
+use crate::player::Player;
+use crate::enemy::{self, Enemy};
+use crate::bunker::{self, Bunker};
+use crate::bullet::Bullet;
+use crate::bomb::{self, Bomb, BombKind};
+use crate::ufo::{Side, Ufo};
+use crate::effect::{Effect, EffectKind};
+use crate::powerup::{self, ActivePowerUp, Capsule, PowerUpKind};
+use crate::level::{Campaign, Level};
+
+// the playfield in logical pixels, the window shows it scaled to whatever size it has
+pub const FIELD_WIDTH: f32 = 700.0;
+pub const FIELD_HEIGHT: f32 = 550.0;
+// the simulation always advances in ticks of this length, whatever the frame rate is
+pub const TICK: f32 = 1.0 / 60.0;
+// longest frame we catch up on, so a stalled window doesn't run minutes of game at once
+const MAX_FRAME_TIME: f32 = 0.25;
+const CLASSIC_ENEMY_POINTS: i32 = 10;
+const UFO_POINTS: i32 = 100;
+// how often a destroyed saucer leaves a capsule behind
+const CAPSULE_DROP_CHANCE: f64 = 0.35;
+// sideways speed of the outer bullets of a spread shot
+const SPREAD_DRIFT: f32 = 90.0;
+// ticks the invaders hold still while the ship explodes, before it respawns
+const DEATH_TICKS: i32 = 120;
+
+// one step of the arcade march, sideways or down at the edges
+const ARCADE_NOTCH: f32 = 6.0;
+const ARCADE_DROP: f32 = 12.0;
+// the formation speed at which an arcade step takes one tick per living invader
+const ARCADE_BASE_SPEED: f32 = 0.4;
+// the squiggly shot drops from these columns in turn, like the arcade's column table
+const SQUIGGLY_COLUMNS: [usize; 15] = [10, 0, 5, 2, 0, 0, 10, 8, 1, 7, 1, 10, 3, 6, 9];
+// the march beat is never slower or faster than this, in seconds
+const MARCH_BEAT_SLOWEST: f32 = 1.0;
+const MARCH_BEAT_FASTEST: f32 = 0.1;
+
+pub enum Dir {
+    Left,
+    Right,
+}
+
+// the whole game simulation, free of any window, texture or keyboard access
+pub struct World {
+    pub state: GameState,
+    pub seed: u64,
+    rng: ChaCha8Rng,
+    pub game: Game,
+    // the missions this world plays
+    pub campaign: Campaign,
+    pub player: Player,
+    pub enemies: Vec<Enemy>,
+    pub bunkers: Vec<Bunker>,
+    pub bullets: Vec<Bullet>,
+    pub bombs: Vec<Bomb>,
+    pub ufo: Vec<Ufo>,
+    pub effects: Vec<Effect>,
+    pub capsules: Vec<Capsule>,
+    pub power_up: Option<ActivePowerUp>,
+    // everything that happened during the last step
+    pub events: Vec<GameEvent>,
+    enemy_direction: Dir,
+    march_timer: i32,
+    squiggly_column: usize,
+    death_timer: i32,
+    fire_timer: i32,
+    // invaders at the start of the mission
+    formation_size: usize,
+    time: f64,
+    accumulator: f32,
+    latched_input: InputFrame,
+    bomb_last_time: f64,
+    ufo_last_time: f64,
+    next_bonus_at: i32,
+    // the last id handed to an invader or a bomb
+    next_id: u32,
+}
+
+fn enemy_points(mode: GameMode, enemy: &Enemy) -> i32 {
+    match mode {
+        GameMode::Classic => CLASSIC_ENEMY_POINTS,
+        GameMode::Arcade => enemy.kind.stats().score,
+    }
+}
+
+impl World {
+    pub fn new(seed: u64, mode: GameMode, campaign: Campaign) -> Self {
+        let enemies = campaign.level(1).make_enemies();
+        let bunkers = campaign.level(1).make_bunkers();
+
+        let mut world = Self {
+            state: GameState::Intro,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            game: Game::new(mode),
+            campaign,
+            player: Player::new(),
+            formation_size: enemies.len(),
+            enemies,
+            bunkers,
+            bullets: Vec::new(),
+            bombs: Vec::new(),
+            ufo: Vec::new(),
+            effects: Vec::new(),
+            capsules: Vec::new(),
+            power_up: None,
+            events: Vec::new(),
+            enemy_direction: Dir::Left,
+            march_timer: 0,
+            squiggly_column: 0,
+            death_timer: 0,
+            fire_timer: 0,
+            time: 0.0,
+            accumulator: 0.0,
+            latched_input: InputFrame::default(),
+            bomb_last_time: 0.0,
+            ufo_last_time: 0.0,
+            next_bonus_at: 1000,
+            next_id: 0,
+        };
+        world.tag_enemies();
+        world
+    }
+
+    pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.game.difficulty = difficulty;
+        self
+    }
+
+    pub fn step(&mut self, dt: f32, input: InputFrame) {
+        self.events.clear();
+
+        // key presses wait for the next tick, so none are lost or applied twice
+        self.latched_input.pause |= input.pause;
+        self.latched_input.confirm |= input.confirm;
+
+        self.accumulator += dt.min(MAX_FRAME_TIME);
+        while self.accumulator >= TICK {
+            let tick_input = InputFrame {
+                pause: self.latched_input.pause,
+                confirm: self.latched_input.confirm,
+                ..input
+            };
+            self.latched_input = InputFrame::default();
+
+            self.tick(tick_input);
+            self.accumulator -= TICK;
+        }
+    }
+
+    // how far between the last two ticks the renderer should draw, 0.0..1.0
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / TICK
+    }
+
+    fn tick(&mut self, input: InputFrame) {
+        self.time += TICK as f64;
+        self.save_positions();
+        let last_state = self.state;
+
+        // explosions finish playing out after the action stops, but not while paused
+        if self.state != GameState::Paused {
+            for effect in &mut self.effects {
+                effect.update();
+            }
+        }
+
+        match self.state {
+            GameState::Intro => {
+                if input.confirm {
+                    self.new_game();
+                }
+            },
+            GameState::InitLevel => {
+                self.bomb_last_time = self.time;
+                self.player.x = 320.0;
+                self.animate_enemies();
+
+                if input.confirm {
+                    self.state = GameState::Game;
+                }
+            },
+            GameState::Game => {
+                self.update_game(input);
+            },
+            GameState::LevelFail => {
+                // space skips the rest of the explosion
+                self.death_timer -= 1;
+                if self.death_timer <= 0 || input.confirm {
+                    self.game.lives -= 1;
+                    self.player.respawn();
+                    self.bullets.clear();
+                    self.bombs.clear();
+                    self.ufo.clear();
+                    self.capsules.clear();
+                    self.state = GameState::Game;
+                }
+            },
+            GameState::Paused => {
+                if input.confirm {
+                    self.state = GameState::Game;
+                }
+            },
+            GameState::LevelCompleted => {
+                if input.confirm {
+                    self.player.x = 320.0;
+                    self.bullets.clear();
+                    self.bombs.clear();
+                    self.ufo.clear();
+                    self.capsules.clear();
+                    self.game.mission += 1;
+                    self.enemies = self.level().make_enemies();
+                    self.tag_enemies();
+                    self.bunkers = self.level().make_bunkers();
+                    self.game.enemy_speed = self.start_speed();
+                    self.reset_march();
+                    self.state = GameState::InitLevel;
+                }
+            },
+            GameState::GameOver => {
+                self.animate_enemies();
+
+                if input.confirm {
+                    // every new game gets its own seed, so each run can be reproduced on its own
+                    self.seed = self.rng.gen();
+                    self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+                    self.new_game();
+                }
+            },
+        }
+
+        if last_state == GameState::Game && self.state != last_state {
+            match self.state {
+                GameState::LevelFail | GameState::GameOver => self.events.push(GameEvent::PlayerDied),
+                GameState::LevelCompleted => self.events.push(GameEvent::MissionCompleted),
+                _ => {},
+            }
+        }
+
+        self.gc();
+    }
+
+    pub fn new_game(&mut self) {
+        self.bullets.clear();
+        self.bombs.clear();
+        self.ufo.clear();
+        self.effects.clear();
+        self.capsules.clear();
+        self.power_up = None;
+        self.player.shield = false;
+        self.game.mission = 1;
+        self.bunkers = self.level().make_bunkers();
+        self.enemies = self.level().make_enemies();
+        self.tag_enemies();
+        self.enemy_direction = Dir::Left;
+        self.squiggly_column = 0;
+        self.reset_march();
+        self.game.score = 0;
+        self.game.lives = self.game.difficulty.stats().lives;
+        self.game.enemy_speed = self.start_speed();
+        self.time = 0.0;
+        self.bomb_last_time = 0.0;
+        self.ufo_last_time = 0.0;
+        self.next_bonus_at = 1000;
+        self.state = GameState::InitLevel;
+    }
+
+    pub fn level(&self) -> &Level {
+        self.campaign.level(self.game.mission)
+    }
+
+    // formation speed at the start of the current mission
+    fn start_speed(&self) -> f32 {
+        self.campaign.start_speed(self.game.mission) * self.game.difficulty.stats().speed
+    }
+
+    // seconds between two notes of the march, following how fast the formation moves
+    pub fn march_interval(&self) -> f32 {
+        let interval = match self.game.mode {
+            GameMode::Classic => {
+                let alive = self.enemies.len() as f32 / self.formation_size.max(1) as f32;
+                let start = self.campaign.start_speed(1);
+                start / self.game.enemy_speed.max(start) * (0.3 + 0.7 * alive)
+            },
+            GameMode::Arcade => self.arcade_step_ticks() as f32 * TICK,
+        };
+
+        interval.clamp(MARCH_BEAT_FASTEST, MARCH_BEAT_SLOWEST)
+    }
+
+    fn reset_march(&mut self) {
+        self.march_timer = 0;
+        self.formation_size = self.enemies.len();
+        if self.game.mode == GameMode::Arcade {
+            self.enemy_direction = Dir::Right;
+        }
+    }
+
+    // a step takes one tick per living invader at ARCADE_BASE_SPEED, and
+    // proportionally fewer as the formation speeds up
+    fn arcade_step_ticks(&self) -> i32 {
+        let rate = self.game.enemy_speed / ARCADE_BASE_SPEED;
+        ((self.enemies.len() as f32 / rate).round() as i32).max(1)
+    }
+
+    // the whole formation moves one notch per step, so the march speeds up as they die
+    fn march_arcade(&mut self) {
+        self.march_timer -= 1;
+        if self.march_timer > 0 {
+            return;
+        }
+        self.march_timer = self.arcade_step_ticks();
+
+        let left = self.enemies.iter().map(|e| e.x).fold(f32::MAX, f32::min);
+        let right = self.enemies.iter().map(|e| e.x + e.rect.w).fold(f32::MIN, f32::max);
+        let at_edge = match self.enemy_direction {
+            Dir::Left => left - ARCADE_NOTCH < 0.0,
+            Dir::Right => right + ARCADE_NOTCH > FIELD_WIDTH,
+        };
+
+        for enemy in &mut self.enemies {
+            if at_edge {
+                enemy.y += ARCADE_DROP;
+            } else {
+                match self.enemy_direction {
+                    Dir::Left => enemy.x -= ARCADE_NOTCH,
+                    Dir::Right => enemy.x += ARCADE_NOTCH,
+                }
+            }
+            enemy.next_frame();
+        }
+
+        if at_edge {
+            self.game.enemy_speed += self.level().speed.step * self.game.difficulty.stats().speed;
+            self.enemy_direction = match self.enemy_direction {
+                Dir::Left => Dir::Right,
+                Dir::Right => Dir::Left,
+            };
+        }
+    }
+
+    // the lowest living invader of every column, the only ones with a clear shot
+    fn bottom_invaders(&self) -> Vec<usize> {
+        let mut bottom: Vec<usize> = Vec::new();
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            match bottom.iter_mut().find(|b| self.enemies[**b].column == enemy.column) {
+                Some(b) => {
+                    if enemy.y > self.enemies[*b].y {
+                        *b = i;
+                    }
+                },
+                None => bottom.push(i),
+            }
+        }
+        bottom
+    }
+
+    // the kinds at the bottom of the formation decide which type of bomb comes next,
+    // weighted by their bomb frequency, and the type decides where it drops from:
+    // rolling bombs from the column above the player, the others from the column table
+    fn pick_bomber(&mut self) -> Option<(usize, BombKind)> {
+        let bottom = self.bottom_invaders();
+        let total: u32 = bottom.iter().map(|&i| self.enemies[i].kind.stats().bomb_frequency).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut pick = self.rng.gen_range(0..total);
+        let picked = *bottom.iter().find(|&&i| {
+            let frequency = self.enemies[i].kind.stats().bomb_frequency;
+            if pick < frequency {
+                return true;
+            }
+            pick -= frequency;
+            false
+        })?;
+        let bombs = self.enemies[picked].kind.stats().bombs;
+        let bomb = bombs[self.rng.gen_range(0..bombs.len())];
+
+        let player_x = self.player.rect.center().x;
+        let bomber = match bomb {
+            BombKind::Rolling => bottom.into_iter().min_by(|&a, &b| {
+                let a = (self.enemies[a].rect.center().x - player_x).abs();
+                let b = (self.enemies[b].rect.center().x - player_x).abs();
+                a.total_cmp(&b)
+            }),
+            BombKind::Squiggly | BombKind::Plunger | BombKind::Splitter => {
+                let columns = self.level().columns().max(1);
+                // empty columns are skipped, at most once round the table
+                let mut found = None;
+                for _ in 0..SQUIGGLY_COLUMNS.len() {
+                    let column = SQUIGGLY_COLUMNS[self.squiggly_column] % columns;
+                    self.squiggly_column = (self.squiggly_column + 1) % SQUIGGLY_COLUMNS.len();
+                    found = bottom.iter().copied().find(|&i| self.enemies[i].column == column);
+                    if found.is_some() {
+                        break;
+                    }
+                }
+                found
+            },
+        };
+
+        bomber.map(|bomber| (bomber, bomb))
+    }
+
+    fn save_positions(&mut self) {
+        self.player.save_position();
+        for enemy in &mut self.enemies {
+            enemy.save_position();
+        }
+        for bullet in &mut self.bullets {
+            bullet.save_position();
+        }
+        for bomb in &mut self.bombs {
+            bomb.save_position();
+        }
+        for ufo in &mut self.ufo {
+            ufo.save_position();
+        }
+        for effect in &mut self.effects {
+            effect.save_position();
+        }
+        for capsule in &mut self.capsules {
+            capsule.save_position();
+        }
+    }
+
+    // arcade invaders only change frame when they march
+    fn animate_enemies(&mut self) {
+        if self.game.mode == GameMode::Arcade {
+            return;
+        }
+        for enemy in &mut self.enemies {
+            enemy.update();
+        }
+    }
+
+    // bullets allowed on screen, ticks between shots and bullets per shot,
+    // as the running power-up changes them
+    fn gun(&self) -> (usize, i32, usize) {
+        let (cap, cooldown) = (self.campaign.gun.max_bullets, self.campaign.gun.fire_cooldown);
+        match self.power_up.as_ref().map(|p| p.kind) {
+            Some(PowerUpKind::RapidFire) => (cap * 3, cooldown / 2, 1),
+            Some(PowerUpKind::SpreadShot) => (cap * 3, cooldown, 3),
+            _ => (cap, cooldown, 1),
+        }
+    }
+
+    fn new_id(&mut self) -> u32 {
+        self.next_id = self.next_id.wrapping_add(1);
+        self.next_id
+    }
+
+    fn tag_enemies(&mut self) {
+        for i in 0..self.enemies.len() {
+            self.enemies[i].id = self.new_id();
+        }
+    }
+
+    fn catch_power_up(&mut self, kind: PowerUpKind) {
+        match kind {
+            PowerUpKind::RapidFire | PowerUpKind::SpreadShot | PowerUpKind::Piercing => {
+                self.power_up = Some(ActivePowerUp { kind, ticks_left: kind.info().duration });
+            },
+            PowerUpKind::Shield => self.player.shield = true,
+            PowerUpKind::BunkerRepair => self.bunkers = self.level().make_bunkers(),
+            PowerUpKind::ExtraLife => {
+                self.game.lives += 1;
+                self.events.push(GameEvent::ExtraLife);
+                return;
+            },
+        }
+        self.events.push(GameEvent::PowerUpCaught);
+    }
+
+    fn update_game(&mut self, input: InputFrame) {
+        // the tick stops here, so nothing that happens after it can be lost to the pause
+        if input.pause {
+            self.state = GameState::Paused;
+            return;
+        }
+
+        // just above the highest bunker, or the ship when there are none
+        let split_height = self.level().bunkers.iter().map(|layout| layout.y).fold(self.player.y, f32::min) - bomb::SPLIT_ABOVE;
+        let mut halves: Vec<Bomb> = Vec::new();
+        for bomb in &mut self.bombs {
+            bomb.update(TICK);
+            if let Some(split) = bomb.split(split_height) {
+                halves.extend(split);
+                continue;
+            }
+
+            if bomb.rect.intersect(self.player.rect).is_some() && self.player.shield && !bomb.destroyed {
+                bomb.destroyed = true;
+                self.player.shield = false;
+                self.effects.push(
+                    Effect::new(EffectKind::Bomb, bomb.rect.center().x, bomb.rect.center().y),
+                );
+            }
+
+            if bomb.rect.intersect(self.player.rect).is_some() && !self.player.is_invulnerable() && !bomb.destroyed {
+                bomb.destroyed = true;
+                self.power_up = None;
+                self.death_timer = DEATH_TICKS;
+                self.effects.push(
+                    Effect::new(EffectKind::Player, self.player.rect.center().x, self.player.rect.center().y),
+                );
+                if self.game.lives > 0 {
+                    self.state = GameState::LevelFail;
+                } else {
+                    self.state = GameState::GameOver;
+                }
+            }
+
+            for bullet in &mut self.bullets {
+                if !bullet.destroyed && !bomb.destroyed && bomb.rect.intersect(bullet.rect).is_some() {
+                    if !bullet.strike(bomb.id) {
+                        continue;
+                    }
+                    if bomb.hit() {
+                        self.game.score += bomb.kind.stats().score;
+                        self.effects.push(
+                            Effect::new(EffectKind::Bomb, bomb.rect.center().x, bomb.rect.center().y),
+                        );
+                    }
+                }
+            }
+        }
+        for mut half in halves {
+            half.id = self.new_id();
+            self.bombs.push(half);
+        }
+
+        // one ufo at a time, the interval counts from when the last one is gone
+        if !self.ufo.is_empty() {
+            self.ufo_last_time = self.time;
+        } else if self.time - self.ufo_last_time > self.level().ufo.interval {
+            let side = match self.rng.gen_range(0..=1) {
+                0 => Side::Left,
+                _ => Side::Right,
+            };
+            self.ufo.push(
+                Ufo::new(side),
+            );
+            self.ufo_last_time = self.time;
+        }
+
+        if let Some(ufo) = self.ufo.first_mut() {
+            ufo.update();
+            for bullet in &mut self.bullets {
+                if !bullet.destroyed && !ufo.destroyed && bullet.rect.intersect(ufo.rect).is_some() {
+                    ufo.destroyed = true;
+                    bullet.destroyed = true;
+                    self.game.score += UFO_POINTS;
+                    self.effects.push(
+                        Effect::new(EffectKind::Ufo, ufo.rect.center().x, ufo.rect.center().y).with_score(UFO_POINTS),
+                    );
+                    if self.rng.gen_bool(CAPSULE_DROP_CHANCE) {
+                        let kind = PowerUpKind::ALL[self.rng.gen_range(0..PowerUpKind::ALL.len())];
+                        self.capsules.push(
+                            Capsule::new(ufo.rect.center().x - powerup::CAPSULE_SIZE * 0.5, ufo.rect.bottom(), kind),
+                        );
+                    }
+                    self.events.push(GameEvent::UfoDestroyed);
+                }
+            }
+        }
+
+        // generate random time between the bombs
+        let bombs = &self.level().bombs;
+        let factor = self.game.difficulty.stats().bomb_interval;
+        let (min_interval, max_extra) = (bombs.min_interval * factor, bombs.max_extra * factor);
+        if self.time - self.bomb_last_time > min_interval {
+            let time_between_bombs: f64 = self.rng.gen_range(0.0..=max_extra);
+
+            if self.time - self.bomb_last_time > min_interval + time_between_bombs {
+                if let Some((bomber_index, bomb)) = self.pick_bomber() {
+                    let bomber = &self.enemies[bomber_index];
+                    let mut bomb = Bomb::new(
+                        bomber.x + bomber.rect.w * 0.5,
+                        bomber.y + enemy::ENEMY_SPRITE_HEIGHT * bomber.scale,
+                        bomb,
+                    );
+                    bomb.id = self.new_id();
+                    self.bombs.push(bomb);
+                    self.bomb_last_time = self.time;
+                }
+            }
+        }
+
+        let mut caught: Vec<PowerUpKind> = Vec::new();
+        for capsule in &mut self.capsules {
+            capsule.update(TICK);
+            if capsule.rect.intersect(self.player.rect).is_some() {
+                capsule.destroyed = true;
+                caught.push(capsule.kind);
+            }
+        }
+        for kind in caught {
+            self.catch_power_up(kind);
+        }
+
+        if let Some(power_up) = &mut self.power_up {
+            power_up.ticks_left -= 1;
+            if power_up.ticks_left <= 0 {
+                self.power_up = None;
+            }
+        }
+
+        if self.fire_timer > 0 {
+            self.fire_timer -= 1;
+        }
+        let (cap, cooldown, shots) = self.gun();
+        if input.fire && self.fire_timer == 0 && self.bullets.len() + shots <= cap {
+            let piercing = matches!(&self.power_up, Some(p) if p.kind == PowerUpKind::Piercing);
+            let drifts: &[f32] = if shots == 3 { &[-SPREAD_DRIFT, 0.0, SPREAD_DRIFT] } else { &[0.0] };
+            for drift in drifts {
+                self.bullets.push(
+                    Bullet { drift: *drift, piercing, ..Bullet::new(self.player.x + 32.0, self.player.y) },
+                );
+            }
+            self.fire_timer = cooldown;
+            self.events.push(GameEvent::PlayerShot);
+        }
+        self.player.update(&input);
+
+        for bullet in &mut self.bullets {
+            bullet.update(TICK);
+        }
+
+        if self.game.mode == GameMode::Arcade {
+            self.march_arcade();
+        }
+
+        let mut need_to_pull_down: bool = false;
+        for enemy in &mut self.enemies {
+            if self.game.mode == GameMode::Arcade {
+                enemy.update_rect();
+            } else {
+                match self.enemy_direction {
+                    Dir::Left => {
+                        enemy.x -= self.game.enemy_speed;
+                        if enemy.x < 0.0 {
+                            need_to_pull_down = true;
+                            self.enemy_direction = Dir::Right;
+                        }
+                    },
+                    Dir::Right => {
+                        enemy.x += self.game.enemy_speed;
+                        if enemy.x > FIELD_WIDTH - enemy.rect.w {
+                            need_to_pull_down = true;
+                            self.enemy_direction = Dir::Left;
+                        }
+                    },
+                }
+
+                enemy.update();
+            }
+
+            if enemy.y + enemy.rect.h > self.player.y {
+                self.state = GameState::GameOver;
+            }
+
+            for bullet in &mut self.bullets {
+                if !bullet.destroyed && !enemy.destroyed && bullet.rect.intersect(enemy.rect).is_some() {
+                    if !bullet.strike(enemy.id) {
+                        continue;
+                    }
+                    if enemy.hit() {
+                        self.game.score += enemy_points(self.game.mode, enemy);
+                        self.effects.push(
+                            Effect::new(EffectKind::Invader, enemy.rect.center().x, enemy.rect.center().y),
+                        );
+                        self.events.push(GameEvent::EnemyKilled);
+                    }
+                }
+            }
+
+            if self.player.rect.intersect(enemy.rect).is_some() {
+                self.state = GameState::GameOver;
+            }
+        }
+
+        for bunker in &mut self.bunkers {
+            for enemy in &self.enemies {
+                bunker.erase(enemy.rect);
+            }
+            for bullet in &mut self.bullets {
+                if bullet.destroyed {
+                    continue;
+                }
+                if let Some((column, row)) = bunker.hit(bullet.rect, false) {
+                    bunker.carve(column, row, &bunker::BULLET_CRATER);
+                    bullet.destroyed = true;
+                    let (x, y) = bunker.pixel_center(column, row);
+                    self.effects.push(
+                        Effect::new(EffectKind::Bunker, x, y),
+                    );
+                }
+            }
+            for bomb in &mut self.bombs {
+                if bomb.destroyed {
+                    continue;
+                }
+                if let Some((column, row)) = bunker.hit(bomb.rect, true) {
+                    bunker.carve(column, row, &bunker::BOMB_CRATER);
+                    bomb.destroyed = true;
+                    self.events.push(GameEvent::BombHitBlock);
+                    let (x, y) = bunker.pixel_center(column, row);
+                    self.effects.push(
+                        Effect::new(EffectKind::Bunker, x, y),
+                    );
+                }
+            }
+        }
+
+        if need_to_pull_down {
+            for enemy in &mut self.enemies {
+                enemy.y += 10.0;
+            }
+            self.game.enemy_speed += self.level().speed.step * self.game.difficulty.stats().speed;
+        }
+
+        if self.game.score > self.game.hi_score {
+            self.game.hi_score = self.game.score;
+        }
+
+        if self.game.score > self.next_bonus_at {
+            self.game.lives += 1;
+            self.next_bonus_at += 1000;
+            self.events.push(GameEvent::ExtraLife);
+        }
+
+        // a ship lost on the last invader's tick still costs the life
+        if self.enemies.is_empty() && self.state == GameState::Game {
+            self.state = GameState::LevelCompleted;
+        }
+    }
+
+    // GC
+    fn gc(&mut self) {
+        self.enemies.retain(|x| !x.destroyed);
+        self.bullets.retain(|x| !x.destroyed);
+        self.bombs.retain(|x| !x.destroyed);
+        self.ufo.retain(|x| !x.destroyed);
+        self.effects.retain(|x| !x.destroyed);
+        self.capsules.retain(|x| !x.destroyed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::Assets;
+
+    // sweeps left and right under the formation with the trigger held,
+    // confirming every prompt along the way
+    fn scripted_input(frame: usize) -> InputFrame {
+        InputFrame {
+            left: frame % 240 < 120,
+            right: frame % 240 >= 120,
+            fire: true,
+            pause: false,
+            confirm: frame.is_multiple_of(90),
+        }
+    }
+
+    // the final world and every state it went through
+    fn play(seed: u64, mode: GameMode, frames: usize) -> (World, Vec<GameState>) {
+        let campaign = Campaign::load(&Assets::embedded(), mode).unwrap();
+        let mut world = World::new(seed, mode, campaign);
+        let mut states = vec![world.state];
+
+        for frame in 0..frames {
+            world.step(TICK, scripted_input(frame));
+            if world.state != *states.last().unwrap() {
+                states.push(world.state);
+            }
+        }
+
+        (world, states)
+    }
+
+    #[test]
+    fn same_seed_and_input_play_the_same() {
+        for mode in GameMode::ALL {
+            let (first, first_states) = play(42, mode, 60 * 90);
+            let (second, second_states) = play(42, mode, 60 * 90);
+
+            assert_eq!(first_states, second_states);
+            assert_eq!(first.game.score, second.game.score);
+            assert_eq!(first.game.lives, second.game.lives);
+            assert_eq!(first.game.mission, second.game.mission);
+            assert_eq!(first.enemies.len(), second.enemies.len());
+            assert_eq!(first.player.x, second.player.x);
+        }
+    }
+
+    // a world in the middle of its first mission, a bomb about to hit the ship
+    fn bomb_on_the_ship() -> World {
+        let campaign = Campaign::load(&Assets::embedded(), GameMode::Classic).unwrap();
+        let mut world = World::new(42, GameMode::Classic, campaign);
+        let confirm = InputFrame { confirm: true, ..InputFrame::default() };
+        world.tick(confirm);
+        world.tick(confirm);
+        world.tick(InputFrame::default());
+        assert_eq!(world.state, GameState::Game);
+
+        world.bombs.push(Bomb::new(world.player.x + 20.0, world.player.y, BombKind::Rolling));
+        world
+    }
+
+    #[test]
+    fn pause_waits_for_the_hit() {
+        let mut world = bomb_on_the_ship();
+        world.tick(InputFrame { pause: true, ..InputFrame::default() });
+        assert_eq!(world.state, GameState::Paused);
+        assert_eq!(world.bombs.len(), 1);
+
+        world.tick(InputFrame { confirm: true, ..InputFrame::default() });
+        world.tick(InputFrame::default());
+        assert_eq!(world.state, GameState::LevelFail);
+    }
+
+    #[test]
+    fn last_invader_does_not_save_the_ship() {
+        let mut world = bomb_on_the_ship();
+        world.enemies.clear();
+        world.tick(InputFrame::default());
+        assert_eq!(world.state, GameState::LevelFail);
+    }
+
+    // pinned results, a change here means recorded replays no longer play the same
+    #[test]
+    fn scripted_run_ends_the_same() {
+        use GameState::*;
+
+        let (world, states) = play(42, GameMode::Classic, 60 * 90);
+        assert_eq!(world.game.score, 385);
+        assert_eq!(world.game.lives, 1);
+        assert_eq!(world.game.mission, 2);
+        assert_eq!(states, [
+            Intro, InitLevel, Game, LevelFail, Game, GameOver, InitLevel, Game,
+            LevelFail, Game, LevelFail, Game, LevelCompleted, InitLevel, Game,
+        ]);
+
+        let (world, states) = play(42, GameMode::Arcade, 60 * 90);
+        assert_eq!(world.game.score, 880);
+        assert_eq!(world.game.lives, 1);
+        assert_eq!(world.game.mission, 1);
+        assert_eq!(states, [Intro, InitLevel, Game, LevelFail, Game, LevelFail, Game]);
+    }
+}
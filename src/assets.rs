@@ -0,0 +1,152 @@
+use macroquad::prelude::*;
+use sim::assets::EMBEDDED_LEVELS;
+use sim::game::GameMode;
+use sim::level::Campaign;
+
+use crate::audio::SoundKey;
+
+pub use sim::assets::{AssetError, Assets};
+
+// every asset is compiled into the binary, paths are relative to the "assets" folder,
+// the levels come with the simulation
+const EMBEDDED: &[(&str, &[u8])] = &[
+    ("images/player.png", include_bytes!("../assets/images/player.png")),
+    ("images/block.png", include_bytes!("../assets/images/block.png")),
+    ("images/bullet.png", include_bytes!("../assets/images/bullet.png")),
+    ("images/intro.png", include_bytes!("../assets/images/intro.png")),
+    ("images/enemy/e_1.png", include_bytes!("../assets/images/enemy/e_1.png")),
+    ("images/enemy/e_2.png", include_bytes!("../assets/images/enemy/e_2.png")),
+    ("images/enemy/a_1.png", include_bytes!("../assets/images/enemy/a_1.png")),
+    ("images/enemy/a_2.png", include_bytes!("../assets/images/enemy/a_2.png")),
+    ("images/enemy/b_1.png", include_bytes!("../assets/images/enemy/b_1.png")),
+    ("images/enemy/b_2.png", include_bytes!("../assets/images/enemy/b_2.png")),
+    ("images/bombs/a_1_bomb.png", include_bytes!("../assets/images/bombs/a_1_bomb.png")),
+    ("images/bombs/a_2_bomb.png", include_bytes!("../assets/images/bombs/a_2_bomb.png")),
+    ("images/bombs/b_1_bomb.png", include_bytes!("../assets/images/bombs/b_1_bomb.png")),
+    ("images/bombs/b_2_bomb.png", include_bytes!("../assets/images/bombs/b_2_bomb.png")),
+    ("images/ufo/ufo_0.png", include_bytes!("../assets/images/ufo/ufo_0.png")),
+    ("images/ufo/ufo_1.png", include_bytes!("../assets/images/ufo/ufo_1.png")),
+    ("images/ufo/ufo_2.png", include_bytes!("../assets/images/ufo/ufo_2.png")),
+    ("images/ufo/ufo_3.png", include_bytes!("../assets/images/ufo/ufo_3.png")),
+    ("images/ufo/ufo_4.png", include_bytes!("../assets/images/ufo/ufo_4.png")),
+    ("fonts/game_font.ttf", include_bytes!("../assets/fonts/game_font.ttf")),
+];
+
+// the game's assets: the embedded files, overridden by the given folder and the user's one
+pub fn open(assets_dir: Option<String>) -> Assets {
+    Assets::new(assets_dir).with_embedded(EMBEDDED)
+}
+
+pub fn image(assets: &Assets, path: &str) -> Result<Image, AssetError> {
+    let bytes = assets.read(path)?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|err| AssetError::Corrupt(path.to_string(), err.to_string()))?
+        .to_rgba8();
+
+    Ok(Image {
+        width: image.width() as u16,
+        height: image.height() as u16,
+        bytes: image.into_raw(),
+    })
+}
+
+pub fn texture(assets: &Assets, path: &str) -> Result<Texture2D, AssetError> {
+    image(assets, path).map(|image| Texture2D::from_image(&image))
+}
+
+pub fn font(assets: &Assets, path: &str) -> Result<Font, AssetError> {
+    let bytes = assets.read(path)?;
+    load_ttf_font_from_bytes(&bytes)
+        .map_err(|err| AssetError::Corrupt(path.to_string(), format!("{:?}", err)))
+}
+
+// validates a file without needing a window, images are fully decoded
+pub fn check(assets: &Assets, path: &str) -> Result<(), AssetError> {
+    let corrupt = |reason: &str| AssetError::Corrupt(path.to_string(), reason.to_string());
+
+    if path.ends_with(".png") {
+        image(assets, path)?;
+    } else if path.ends_with(".ttf") {
+        let bytes = assets.read(path)?;
+        if !bytes.starts_with(&[0, 1, 0, 0]) && !bytes.starts_with(b"true") && !bytes.starts_with(b"OTTO") {
+            return Err(corrupt("not a TrueType font"));
+        }
+    } else if path.ends_with(".toml") {
+        let bytes = assets.read(path)?;
+        let text = std::str::from_utf8(&bytes).map_err(|_| corrupt("not UTF-8 text"))?;
+        text.parse::<toml::Table>().map_err(|err| corrupt(err.message()))?;
+    } else if path.ends_with(".ogg") {
+        let bytes = assets.read(path)?;
+        if !bytes.starts_with(b"OggS") {
+            return Err(corrupt("not an Ogg file"));
+        }
+    } else if path.ends_with(".wav") {
+        let bytes = assets.read(path)?;
+        if !bytes.starts_with(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+            return Err(corrupt("not a WAV file"));
+        }
+    }
+
+    Ok(())
+}
+
+// prints the state of every asset, the result is the process exit code
+pub fn check_all(assets: &Assets) -> i32 {
+    let mut code = 0;
+
+    for (path, _) in EMBEDDED.iter().chain(EMBEDDED_LEVELS) {
+        match check(assets, path) {
+            Ok(()) => println!("ok     {}", path),
+            Err(err) => {
+                println!("error  {}", err);
+                code = 1;
+            },
+        }
+    }
+
+    // sound files are optional, each one found replaces a synthesized sound
+    for key in SoundKey::ALL {
+        match check(assets, key.path()) {
+            Ok(()) => println!("ok     {}", key.path()),
+            Err(AssetError::Missing(_)) => println!("synth  {}", key.path()),
+            Err(err) => {
+                println!("error  {}", err);
+                code = 1;
+            },
+        }
+    }
+
+    // every level a campaign lists, including ones only found in the override folder
+    for mode in GameMode::ALL {
+        match Campaign::load(assets, mode) {
+            Ok(campaign) => println!("ok     {} ({} levels)", Campaign::path(mode), campaign.levels.len()),
+            Err(err) => {
+                println!("error  {}", err);
+                code = 1;
+            },
+        }
+    }
+
+    code
+}
+
+// magenta and black checkerboard standing in for a sprite that failed to load
+pub fn placeholder_texture(width: u16, height: u16) -> Texture2D {
+    let mut image = Image::gen_image_color(width, height, MAGENTA);
+    for y in 0..height {
+        for x in 0..width {
+            if (x / 4 + y / 4) % 2 == 1 {
+                image.set_pixel(x as u32, y as u32, BLACK);
+            }
+        }
+    }
+
+    Texture2D::from_image(&image)
+}
+
+pub fn texture_or_placeholder(assets: &Assets, path: &str, width: u16, height: u16, errors: &mut Vec<AssetError>) -> Texture2D {
+    texture(assets, path).unwrap_or_else(|err| {
+        errors.push(err);
+        placeholder_texture(width, height)
+    })
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use macroquad::audio::{self, PlaySoundParams, Sound};
+
+use sim::game::{GameEvent, GameState};
+use sim::synth::{self, Voice};
+use sim::world::World;
+
+use crate::assets::{AssetError, Assets};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SoundKey {
+    Shot,
+    InvaderKilled,
+    BombHitBlock,
+    PlayerDeath,
+    UfoLoop,
+    UfoDestroyed,
+    ExtraLife,
+    MissionComplete,
+    PowerUp,
+    March1,
+    March2,
+    March3,
+    March4,
+}
+
+impl SoundKey {
+    pub const ALL: [SoundKey; 13] = [
+        SoundKey::Shot,
+        SoundKey::InvaderKilled,
+        SoundKey::BombHitBlock,
+        SoundKey::PlayerDeath,
+        SoundKey::UfoLoop,
+        SoundKey::UfoDestroyed,
+        SoundKey::ExtraLife,
+        SoundKey::MissionComplete,
+        SoundKey::PowerUp,
+        SoundKey::March1,
+        SoundKey::March2,
+        SoundKey::March3,
+        SoundKey::March4,
+    ];
+
+    // the four descending bass notes of the invaders' march
+    pub const MARCH: [SoundKey; 4] = [
+        SoundKey::March1,
+        SoundKey::March2,
+        SoundKey::March3,
+        SoundKey::March4,
+    ];
+
+    // a file at this path replaces the synthesized sound
+    pub fn path(self) -> &'static str {
+        match self {
+            SoundKey::Shot => "sounds/laser.ogg",
+            SoundKey::InvaderKilled => "sounds/invader_killed.wav",
+            SoundKey::BombHitBlock => "sounds/bomb_hit_block.wav",
+            SoundKey::PlayerDeath => "sounds/player_death.wav",
+            SoundKey::UfoLoop => "sounds/ufo_loop.wav",
+            SoundKey::UfoDestroyed => "sounds/ufo_destroyed.wav",
+            SoundKey::ExtraLife => "sounds/extra_life.wav",
+            SoundKey::MissionComplete => "sounds/mission_complete.wav",
+            SoundKey::PowerUp => "sounds/power_up.wav",
+            SoundKey::March1 => "sounds/march_1.wav",
+            SoundKey::March2 => "sounds/march_2.wav",
+            SoundKey::March3 => "sounds/march_3.wav",
+            SoundKey::March4 => "sounds/march_4.wav",
+        }
+    }
+
+    pub fn voice(self) -> Voice {
+        match self {
+            SoundKey::Shot => synth::laser(),
+            SoundKey::InvaderKilled => synth::explosion(0.3),
+            SoundKey::BombHitBlock => synth::explosion(0.12),
+            SoundKey::PlayerDeath => synth::explosion(1.0),
+            SoundKey::UfoLoop => synth::ufo_warble(),
+            SoundKey::UfoDestroyed => synth::ufo_hit(),
+            SoundKey::ExtraLife => synth::fanfare(0.4),
+            SoundKey::MissionComplete => synth::fanfare(0.9),
+            SoundKey::PowerUp => synth::fanfare(0.25),
+            SoundKey::March1 => synth::march_note(0),
+            SoundKey::March2 => synth::march_note(1),
+            SoundKey::March3 => synth::march_note(2),
+            SoundKey::March4 => synth::march_note(3),
+        }
+    }
+
+    fn for_event(event: GameEvent) -> Self {
+        match event {
+            GameEvent::PlayerShot => SoundKey::Shot,
+            GameEvent::EnemyKilled => SoundKey::InvaderKilled,
+            GameEvent::BombHitBlock => SoundKey::BombHitBlock,
+            GameEvent::PlayerDied => SoundKey::PlayerDeath,
+            GameEvent::UfoDestroyed => SoundKey::UfoDestroyed,
+            GameEvent::ExtraLife => SoundKey::ExtraLife,
+            GameEvent::MissionCompleted => SoundKey::MissionComplete,
+            GameEvent::PowerUpCaught => SoundKey::PowerUp,
+        }
+    }
+}
+
+pub struct Audio {
+    sounds: HashMap<SoundKey, Sound>,
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub muted: bool,
+    ufo_loop_playing: bool,
+    march_timer: f32,
+    march_note: usize,
+}
+
+impl Audio {
+    pub async fn load(assets: &Assets) -> Self {
+        let mut sounds: HashMap<SoundKey, Sound> = HashMap::new();
+
+        for key in SoundKey::ALL {
+            let file = match assets.read(key.path()) {
+                Ok(bytes) => audio::load_sound_from_bytes(&bytes).await.map_err(|err| {
+                    eprintln!("{}", AssetError::Corrupt(key.path().to_string(), err.to_string()));
+                }).ok(),
+                Err(_) => None,
+            };
+
+            let sound = match file {
+                Some(sound) => sound,
+                None => {
+                    let wav = synth::to_wav(&key.voice().render());
+                    match audio::load_sound_from_bytes(&wav).await {
+                        Ok(sound) => sound,
+                        Err(_) => continue,
+                    }
+                },
+            };
+            sounds.insert(key, sound);
+        }
+
+        Self {
+            sounds,
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            muted: false,
+            ufo_loop_playing: false,
+            march_timer: 0.0,
+            march_note: 0,
+        }
+    }
+
+    fn sfx_level(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * self.sfx_volume
+        }
+    }
+
+    fn play(&self, key: SoundKey, looped: bool) {
+        if let Some(sound) = self.sounds.get(&key) {
+            audio::play_sound(*sound, PlaySoundParams {
+                looped,
+                volume: self.sfx_level(),
+            });
+        }
+    }
+
+    fn stop(&self, key: SoundKey) {
+        if let Some(sound) = self.sounds.get(&key) {
+            audio::stop_sound(*sound);
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        self.apply_volume();
+    }
+
+    pub fn set_volume(&mut self, master_volume: f32, sfx_volume: f32) {
+        self.master_volume = master_volume.clamp(0.0, 1.0);
+        self.sfx_volume = sfx_volume.clamp(0.0, 1.0);
+        self.apply_volume();
+    }
+
+    // one-shot sounds are played at the current level, loops need to follow changes
+    fn apply_volume(&self) {
+        if let Some(sound) = self.sounds.get(&SoundKey::UfoLoop) {
+            audio::set_sound_volume(*sound, self.sfx_level());
+        }
+    }
+
+    // the beat runs only while playing, holds while paused and starts over otherwise
+    fn update_march(&mut self, world: &World, dt: f32) {
+        match world.state {
+            GameState::Game => {
+                self.march_timer -= dt;
+                if self.march_timer <= 0.0 {
+                    if self.sfx_level() > 0.0 {
+                        self.play(SoundKey::MARCH[self.march_note], false);
+                    }
+                    self.march_note = (self.march_note + 1) % SoundKey::MARCH.len();
+                    self.march_timer = world.march_interval();
+                }
+            },
+            GameState::Paused => {},
+            _ => {
+                if self.march_timer != 0.0 || self.march_note != 0 {
+                    for key in SoundKey::MARCH {
+                        self.stop(key);
+                    }
+                }
+                self.march_timer = 0.0;
+                self.march_note = 0;
+            },
+        }
+    }
+
+    // for when the world that was driving the loops goes away
+    pub fn stop_loops(&mut self) {
+        self.stop(SoundKey::UfoLoop);
+        for key in SoundKey::MARCH {
+            self.stop(key);
+        }
+        self.ufo_loop_playing = false;
+        self.march_timer = 0.0;
+        self.march_note = 0;
+    }
+
+    // one-shots come from the events of the last step, loops follow the state of the world
+    pub fn update(&mut self, world: &World, dt: f32) {
+        if self.sfx_level() > 0.0 {
+            for event in &world.events {
+                self.play(SoundKey::for_event(*event), false);
+            }
+        }
+
+        let ufo_flying = world.state == GameState::Game && !world.ufo.is_empty();
+        if ufo_flying != self.ufo_loop_playing {
+            if ufo_flying {
+                self.play(SoundKey::UfoLoop, true);
+            } else {
+                self.stop(SoundKey::UfoLoop);
+            }
+            self.ufo_loop_playing = ufo_flying;
+        }
+
+        self.update_march(world, dt);
+    }
+}
@@ -0,0 +1,74 @@
+use macroquad::prelude::*;
+
+use sim::world::{FIELD_HEIGHT, FIELD_WIDTH};
+
+// sharp pixels for whole multiples, smooth ones when the playfield is stretched to fit
+fn filter(integer_scaling: bool) -> FilterMode {
+    if integer_scaling {
+        FilterMode::Nearest
+    } else {
+        FilterMode::Linear
+    }
+}
+
+// the playfield is drawn offscreen at its own size, then scaled into the window
+// with black bars on the sides that don't fit
+pub struct Canvas {
+    target: RenderTarget,
+    camera: Camera2D,
+    integer_scaling: bool,
+}
+
+impl Canvas {
+    pub fn new(integer_scaling: bool) -> Self {
+        let target = render_target(FIELD_WIDTH as u32, FIELD_HEIGHT as u32);
+        target.texture.set_filter(filter(integer_scaling));
+        let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, FIELD_WIDTH, FIELD_HEIGHT));
+        camera.render_target = Some(target);
+
+        Self {
+            target,
+            camera,
+            integer_scaling,
+        }
+    }
+
+    pub fn set_integer_scaling(&mut self, integer_scaling: bool) {
+        if integer_scaling != self.integer_scaling {
+            self.integer_scaling = integer_scaling;
+            self.target.texture.set_filter(filter(integer_scaling));
+        }
+    }
+
+    // where the playfield lands in the window, a window smaller than it always gets the whole field
+    pub fn viewport(&self) -> Rect {
+        let mut scale = (screen_width() / FIELD_WIDTH).min(screen_height() / FIELD_HEIGHT);
+        if self.integer_scaling && scale >= 1.0 {
+            scale = scale.floor();
+        }
+
+        let (w, h) = (FIELD_WIDTH * scale, FIELD_HEIGHT * scale);
+        Rect::new(((screen_width() - w) * 0.5).floor(), ((screen_height() - h) * 0.5).floor(), w, h)
+    }
+
+    // everything drawn until present() lands on the playfield, in its own coordinates
+    pub fn begin(&self) {
+        set_camera(&self.camera);
+        clear_background(BLACK);
+    }
+
+    pub fn present(&self) {
+        set_default_camera();
+        clear_background(BLACK);
+
+        let viewport = self.viewport();
+        draw_texture_ex(self.target.texture, viewport.x, viewport.y, WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(viewport.w, viewport.h)),
+                // render targets come out upside down
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
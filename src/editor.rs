@@ -0,0 +1,434 @@
+use std::fs;
+use egui_macroquad::egui;
+use macroquad::prelude::*;
+
+use sim::bunker::{self, BUNKER_PIXEL};
+use sim::enemy::{self, EnemyKind};
+use sim::game::GameMode;
+use sim::level::{BunkerLayout, Campaign, Gun, Level};
+use sim::world::World;
+
+use crate::assets::Assets;
+use crate::audio::Audio;
+use crate::canvas::Canvas;
+use crate::input::{self, Bindings};
+use crate::render;
+use crate::resources::{self, Resources};
+use crate::sprites::SpriteKey;
+
+const PANEL_WIDTH: f32 = 240.0;
+// bunker pixels painted around the mouse, in every direction
+const BRUSH_RADIUS: i32 = 2;
+const BUNKER_COLOR: Color = Color::new(0.0, 0.89, 0.19, 1.0);
+
+// what the left mouse button paints, the right one always erases
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Brush {
+    Invader(EnemyKind),
+    Bunker,
+}
+
+// where the playfield is drawn next to the panel, and how much smaller
+struct View {
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+impl View {
+    fn to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x + x * self.scale, self.y + y * self.scale)
+    }
+
+    fn to_world(&self, x: f32, y: f32) -> (f32, f32) {
+        ((x - self.x) / self.scale, (y - self.y) / self.scale)
+    }
+}
+
+// lays out a mission on the grid and in the side panel, then plays it right away
+pub struct Editor {
+    level: Level,
+    mode: GameMode,
+    // the gun of every mode's campaign, in the order of GameMode::ALL
+    guns: Vec<Gun>,
+    seed: u64,
+    brush: Brush,
+    // level file to save to or load from, inside the levels folder
+    file_name: String,
+    status: String,
+    testing: Option<World>,
+}
+
+impl Editor {
+    pub fn new(level: Level, mode: GameMode, seed: u64, campaigns: &[Campaign]) -> Self {
+        Self {
+            level,
+            mode,
+            guns: campaigns.iter().map(|campaign| campaign.gun.clone()).collect(),
+            seed,
+            brush: Brush::Invader(EnemyKind::Squid),
+            file_name: String::from("custom.toml"),
+            status: String::new(),
+            testing: None,
+        }
+    }
+
+    // false once the editor is closed, it keeps the level for the next time it's opened
+    pub fn update(&mut self, assets: &Assets, resources: &Resources, canvas: &Canvas, controls: &Bindings, audio: &mut Audio) -> bool {
+        if let Some(world) = &mut self.testing {
+            let dt = get_frame_time();
+            world.step(dt, input::from_keyboard(controls));
+            audio.update(world, dt);
+            canvas.begin();
+            render::draw_world(world, resources, controls);
+            canvas.present();
+            draw_text_ex("tab: back to the editor", 10.0, 20.0,
+                TextParams {
+                    font: resources.font,
+                    font_size: 15,
+                    color: GRAY,
+                    ..Default::default()
+                },
+            );
+
+            if is_key_pressed(KeyCode::Tab) {
+                audio.stop_loops();
+                self.testing = None;
+            }
+            return true;
+        }
+
+        let mut open = true;
+        let mut panel_width = PANEL_WIDTH;
+        let mut over_panel = false;
+        let mut typing = false;
+
+        egui_macroquad::ui(|ctx| {
+            let panel = egui::SidePanel::right("editor")
+                .resizable(false)
+                .default_width(PANEL_WIDTH)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        open = self.panel(ui, assets);
+                    });
+                });
+            panel_width = panel.response.rect.width();
+            over_panel = ctx.is_pointer_over_area() || ctx.wants_pointer_input();
+            typing = ctx.wants_keyboard_input();
+        });
+
+        let scale = ((screen_width() - panel_width) / resources::WINDOW_WIDTH as f32)
+            .min(screen_height() / resources::WINDOW_HEIGHT as f32);
+        let view = View {
+            x: 0.0,
+            y: (screen_height() - resources::WINDOW_HEIGHT as f32 * scale) * 0.5,
+            scale,
+        };
+
+        if !over_panel {
+            self.paint(&view);
+        }
+        self.draw(&view, resources);
+        egui_macroquad::draw();
+
+        if is_key_pressed(KeyCode::Escape) && !typing {
+            open = false;
+        }
+        open
+    }
+
+    // the side panel, false when asked to leave
+    fn panel(&mut self, ui: &mut egui::Ui, assets: &Assets) -> bool {
+        let mut open = true;
+
+        ui.heading("Level editor");
+        ui.horizontal(|ui| {
+            ui.label("name");
+            ui.text_edit_singleline(&mut self.level.name);
+        });
+
+        ui.separator();
+        ui.label("left click paints, right click erases");
+        ui.horizontal_wrapped(|ui| {
+            for kind in EnemyKind::ALL {
+                ui.selectable_value(&mut self.brush, Brush::Invader(kind), format!("{:?}", kind));
+            }
+            ui.selectable_value(&mut self.brush, Brush::Bunker, "Bunker");
+        });
+
+        ui.separator();
+        ui.label("formation");
+        let (mut columns, mut rows) = (self.level.columns(), self.level.formation.grid.len());
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut columns).clamp_range(1..=20).prefix("columns "));
+            ui.add(egui::DragValue::new(&mut rows).clamp_range(1..=10).prefix("rows "));
+        });
+        if columns != self.level.columns() || rows != self.level.formation.grid.len() {
+            self.resize_grid(columns, rows);
+        }
+        let formation = &mut self.level.formation;
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut formation.left).clamp_range(0.0..=700.0).prefix("left "));
+            ui.add(egui::DragValue::new(&mut formation.top).clamp_range(0.0..=550.0).prefix("top "));
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut formation.column_pitch).clamp_range(10.0..=200.0).prefix("pitch x "));
+            ui.add(egui::DragValue::new(&mut formation.row_pitch).clamp_range(10.0..=200.0).prefix("y "));
+        });
+        ui.add(egui::DragValue::new(&mut formation.scale).speed(0.01).clamp_range(0.2..=2.0).prefix("scale "));
+
+        ui.separator();
+        ui.label("speed, bombs and ufo");
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.level.speed.start).speed(0.05).clamp_range(0.05..=10.0).prefix("start "));
+            ui.add(egui::DragValue::new(&mut self.level.speed.step).speed(0.05).clamp_range(0.0..=5.0).prefix("step "));
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.level.bombs.min_interval).speed(0.05).clamp_range(0.0..=60.0).prefix("bombs "));
+            ui.add(egui::DragValue::new(&mut self.level.bombs.max_extra).speed(0.5).clamp_range(0.0..=120.0).prefix("+ "));
+        });
+        ui.add(egui::DragValue::new(&mut self.level.ufo.interval).speed(0.1).clamp_range(0.5..=120.0).prefix("ufo every ").suffix(" s"));
+
+        ui.separator();
+        ui.label("bunkers");
+        let mut remove = None;
+        for (i, layout) in self.level.bunkers.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut layout.x).clamp_range(0.0..=700.0).prefix("x "));
+                ui.add(egui::DragValue::new(&mut layout.y).clamp_range(0.0..=550.0).prefix("y "));
+                if ui.small_button("arch").clicked() {
+                    layout.shape.clear();
+                }
+                if ui.small_button("x").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.level.bunkers.remove(i);
+        }
+        if ui.button("add bunker").clicked() {
+            self.level.bunkers.push(BunkerLayout { x: 300.0, y: 400.0, shape: Vec::new() });
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            for mode in GameMode::ALL {
+                ui.selectable_value(&mut self.mode, mode, mode.name());
+            }
+        });
+        if ui.button("play-test").clicked() {
+            self.play_test();
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("levels/");
+            ui.text_edit_singleline(&mut self.file_name);
+        });
+        ui.horizontal(|ui| {
+            if ui.button("save").clicked() {
+                self.save(assets);
+            }
+            if ui.button("load").clicked() {
+                self.load(assets);
+            }
+            if ui.button("back").clicked() {
+                open = false;
+            }
+        });
+        if !self.status.is_empty() {
+            ui.label(self.status.as_str());
+        }
+
+        open
+    }
+
+    fn resize_grid(&mut self, columns: usize, rows: usize) {
+        let grid = &mut self.level.formation.grid;
+        grid.resize(rows, String::new());
+        for line in grid.iter_mut() {
+            let mut letters: Vec<char> = line.chars().collect();
+            letters.resize(columns, '.');
+            *line = letters.into_iter().collect();
+        }
+    }
+
+    fn set_cell(&mut self, column: usize, row: usize, letter: char) {
+        if let Some(line) = self.level.formation.grid.get_mut(row) {
+            let mut letters: Vec<char> = line.chars().collect();
+            if let Some(cell) = letters.get_mut(column) {
+                *cell = letter;
+            }
+            *line = letters.into_iter().collect();
+        }
+    }
+
+    // bunker pixels around a point become standing or empty, standing ones off
+    // the edge grow the shape
+    fn paint_bunker(&mut self, x: f32, y: f32, standing: bool) {
+        for layout in &mut self.level.bunkers {
+            // the arch only becomes an explicit shape once it's painted on
+            let shape = if layout.shape.is_empty() { bunker::arch_shape() } else { layout.shape.clone() };
+            let bunker = bunker::Bunker::new(layout.x, layout.y, &shape);
+            let column = ((x - layout.x) / BUNKER_PIXEL).floor() as i32;
+            let row = ((y - layout.y) / BUNKER_PIXEL).floor() as i32;
+            if column < -BRUSH_RADIUS || row < -BRUSH_RADIUS
+                || column > bunker.columns as i32 + BRUSH_RADIUS || row > bunker.rows as i32 + BRUSH_RADIUS {
+                continue;
+            }
+
+            let mut brush: Vec<(i32, i32)> = Vec::new();
+            for dy in -BRUSH_RADIUS..=BRUSH_RADIUS {
+                for dx in -BRUSH_RADIUS..=BRUSH_RADIUS {
+                    if dx * dx + dy * dy <= BRUSH_RADIUS * BRUSH_RADIUS {
+                        brush.push((column + dx, row + dy));
+                    }
+                }
+            }
+
+            // the shape grows to take in the brush, shifting it right and down when
+            // it grows left or up, so the pixels already there stay where they are
+            let (mut left, mut top) = (0, 0);
+            let (mut right, mut bottom) = (bunker.columns as i32, bunker.rows as i32);
+            if standing {
+                for &(c, r) in &brush {
+                    left = left.min(c);
+                    top = top.min(r);
+                    right = right.max(c + 1);
+                    bottom = bottom.max(r + 1);
+                }
+            }
+
+            let mut pixels: Vec<Vec<bool>> = (top..bottom).map(|r| {
+                (left..right).map(|c| {
+                    (0..bunker.columns as i32).contains(&c) && (0..bunker.rows as i32).contains(&r)
+                        && bunker.is_solid(c as usize, r as usize)
+                }).collect()
+            }).collect();
+            for (c, r) in brush {
+                if (left..right).contains(&c) && (top..bottom).contains(&r) {
+                    pixels[(r - top) as usize][(c - left) as usize] = standing;
+                }
+            }
+
+            layout.x += left as f32 * BUNKER_PIXEL;
+            layout.y += top as f32 * BUNKER_PIXEL;
+            layout.shape = pixels.iter().map(|line| {
+                line.iter().map(|&solid| if solid { '#' } else { '.' }).collect()
+            }).collect();
+        }
+    }
+
+    fn paint(&mut self, view: &View) {
+        let left = is_mouse_button_down(MouseButton::Left);
+        let right = is_mouse_button_down(MouseButton::Right);
+        if !left && !right {
+            return;
+        }
+
+        let (mx, my) = mouse_position();
+        let (x, y) = view.to_world(mx, my);
+        match self.brush {
+            Brush::Invader(kind) => {
+                let formation = &self.level.formation;
+                let column = ((x - formation.left) / formation.column_pitch).floor();
+                let row = ((y - formation.top) / formation.row_pitch).floor();
+                if column >= 0.0 && row >= 0.0 {
+                    self.set_cell(column as usize, row as usize, if left { kind.letter() } else { '.' });
+                }
+            },
+            Brush::Bunker => self.paint_bunker(x, y, left),
+        }
+    }
+
+    fn draw(&self, view: &View, resources: &Resources) {
+        let (x, y) = view.to_screen(0.0, 0.0);
+        draw_rectangle_lines(x, y, resources::WINDOW_WIDTH as f32 * view.scale, resources::WINDOW_HEIGHT as f32 * view.scale, 1.0, DARKGRAY);
+        let (x, y) = view.to_screen(320.0, 480.0);
+        draw_texture_ex(resources.player_texture, x, y, WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(resources.player_texture.width(), resources.player_texture.height()) * view.scale),
+                ..Default::default()
+            });
+
+        let formation = &self.level.formation;
+        for (row, line) in formation.grid.iter().enumerate() {
+            for (column, letter) in line.chars().enumerate() {
+                let (x, y) = view.to_screen(
+                    formation.left + column as f32 * formation.column_pitch,
+                    formation.top + row as f32 * formation.row_pitch);
+                draw_rectangle_lines(x, y,
+                    enemy::ENEMY_WIDTH * formation.scale * view.scale,
+                    enemy::ENEMY_HEIGHT * formation.scale * view.scale,
+                    1.0, Color::new(0.3, 0.3, 0.3, 1.0));
+
+                if let Some(kind) = EnemyKind::from_letter(letter) {
+                    let texture = resources.sprites.frame(SpriteKey::enemy(kind), 0);
+                    draw_texture_ex(texture, x, y, WHITE,
+                        DrawTextureParams {
+                            dest_size: Some(vec2(texture.width(), texture.height()) * formation.scale * view.scale),
+                            ..Default::default()
+                        });
+                }
+            }
+        }
+
+        let pixel = BUNKER_PIXEL * view.scale;
+        for bunker in self.level.make_bunkers() {
+            for row in 0..bunker.rows {
+                for column in 0..bunker.columns {
+                    if bunker.is_solid(column, row) {
+                        let (x, y) = view.to_screen(bunker.x + column as f32 * BUNKER_PIXEL, bunker.y + row as f32 * BUNKER_PIXEL);
+                        draw_rectangle(x, y, pixel, pixel, BUNKER_COLOR);
+                    }
+                }
+            }
+        }
+    }
+
+    fn play_test(&mut self) {
+        if let Err(reason) = self.level.validate() {
+            self.status = reason;
+            return;
+        }
+
+        // played with the gun of the mode's campaign, like the level will be once it's listed there
+        let gun = self.guns[GameMode::ALL.iter().position(|mode| *mode == self.mode).unwrap()].clone();
+        let mut world = World::new(self.seed, self.mode, Campaign::single(self.level.clone()).with_gun(gun));
+        world.new_game();
+        self.testing = Some(world);
+        self.status.clear();
+    }
+
+    fn save(&mut self, assets: &Assets) {
+        if let Err(reason) = self.level.validate() {
+            self.status = reason;
+            return;
+        }
+
+        let path = match assets.save_path(&Campaign::level_path(&self.file_name)) {
+            Some(path) => path,
+            None => {
+                self.status = "no folder to save into, start the game with --assets-dir".to_string();
+                return;
+            },
+        };
+        let result = path.parent().map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, self.level.to_toml()));
+        self.status = match result {
+            Ok(()) => format!("saved to {}", path.display()),
+            Err(err) => format!("can't save {}: {}", path.display(), err),
+        };
+    }
+
+    fn load(&mut self, assets: &Assets) {
+        match Level::load(assets, &Campaign::level_path(&self.file_name)) {
+            Ok(level) => {
+                self.level = level;
+                self.status = format!("loaded {}", self.file_name);
+            },
+            Err(err) => self.status = err.to_string(),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+use sim::input::InputFrame;
+
+// most keys an action can have, binding another one drops the oldest
+pub const MAX_BINDINGS: usize = 3;
+
+// keys that can be bound, by the name used in the settings file
+const KEY_NAMES: [(KeyCode, &str); 73] = [
+    (KeyCode::Left, "left"), (KeyCode::Right, "right"), (KeyCode::Up, "up"), (KeyCode::Down, "down"),
+    (KeyCode::Space, "space"), (KeyCode::Enter, "enter"), (KeyCode::Escape, "escape"), (KeyCode::Tab, "tab"),
+    (KeyCode::Backspace, "backspace"), (KeyCode::Insert, "insert"), (KeyCode::Delete, "delete"),
+    (KeyCode::Home, "home"), (KeyCode::End, "end"), (KeyCode::PageUp, "page_up"), (KeyCode::PageDown, "page_down"),
+    (KeyCode::LeftShift, "left_shift"), (KeyCode::RightShift, "right_shift"),
+    (KeyCode::LeftControl, "left_control"), (KeyCode::RightControl, "right_control"),
+    (KeyCode::LeftAlt, "left_alt"), (KeyCode::RightAlt, "right_alt"),
+    (KeyCode::A, "a"), (KeyCode::B, "b"), (KeyCode::C, "c"), (KeyCode::D, "d"), (KeyCode::E, "e"),
+    (KeyCode::F, "f"), (KeyCode::G, "g"), (KeyCode::H, "h"), (KeyCode::I, "i"), (KeyCode::J, "j"),
+    (KeyCode::K, "k"), (KeyCode::L, "l"), (KeyCode::M, "m"), (KeyCode::N, "n"), (KeyCode::O, "o"),
+    (KeyCode::P, "p"), (KeyCode::Q, "q"), (KeyCode::R, "r"), (KeyCode::S, "s"), (KeyCode::T, "t"),
+    (KeyCode::U, "u"), (KeyCode::V, "v"), (KeyCode::W, "w"), (KeyCode::X, "x"), (KeyCode::Y, "y"),
+    (KeyCode::Z, "z"),
+    (KeyCode::Key0, "0"), (KeyCode::Key1, "1"), (KeyCode::Key2, "2"), (KeyCode::Key3, "3"), (KeyCode::Key4, "4"),
+    (KeyCode::Key5, "5"), (KeyCode::Key6, "6"), (KeyCode::Key7, "7"), (KeyCode::Key8, "8"), (KeyCode::Key9, "9"),
+    (KeyCode::Kp0, "kp_0"), (KeyCode::Kp1, "kp_1"), (KeyCode::Kp2, "kp_2"), (KeyCode::Kp3, "kp_3"),
+    (KeyCode::Kp4, "kp_4"), (KeyCode::Kp5, "kp_5"), (KeyCode::Kp6, "kp_6"), (KeyCode::Kp7, "kp_7"),
+    (KeyCode::Kp8, "kp_8"), (KeyCode::Kp9, "kp_9"), (KeyCode::KpEnter, "kp_enter"),
+    (KeyCode::Comma, "comma"), (KeyCode::Period, "period"), (KeyCode::Slash, "slash"),
+    (KeyCode::Semicolon, "semicolon"), (KeyCode::Apostrophe, "apostrophe"),
+];
+
+// a key the player can bind, stored by name
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key(pub KeyCode);
+
+impl Key {
+    pub fn from_code(code: KeyCode) -> Option<Self> {
+        KEY_NAMES.iter().find(|(c, _)| *c == code).map(|_| Key(code))
+    }
+
+    pub fn name(self) -> &'static str {
+        KEY_NAMES.iter().find(|(c, _)| *c == self.0).map_or("?", |(_, name)| name)
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        KEY_NAMES.iter().find(|(_, n)| *n == name).map(|(code, _)| Key(*code))
+            .ok_or_else(|| format!("unknown key '{}'", name))
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        key.name().to_string()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Pause,
+    Confirm,
+}
+
+impl Action {
+    pub const ALL: [Action; 5] = [Action::MoveLeft, Action::MoveRight, Action::Fire, Action::Pause, Action::Confirm];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move left",
+            Action::MoveRight => "move right",
+            Action::Fire => "fire",
+            Action::Pause => "pause",
+            Action::Confirm => "confirm",
+        }
+    }
+}
+
+// the keys of every action, any of them triggers it
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Bindings {
+    #[serde(deserialize_with = "known_keys")]
+    pub move_left: Vec<Key>,
+    #[serde(deserialize_with = "known_keys")]
+    pub move_right: Vec<Key>,
+    #[serde(deserialize_with = "known_keys")]
+    pub fire: Vec<Key>,
+    #[serde(deserialize_with = "known_keys")]
+    pub pause: Vec<Key>,
+    #[serde(deserialize_with = "known_keys")]
+    pub confirm: Vec<Key>,
+}
+
+// a typo only loses that key, an action left without any gets its defaults back in fill_defaults
+fn known_keys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Key>, D::Error> {
+    let value = toml::Value::deserialize(deserializer)?;
+    let Some(names) = value.as_array() else {
+        eprintln!("can't read controls: expected a list of key names, found {}", value);
+        return Ok(Vec::new());
+    };
+
+    Ok(names.iter().filter_map(|name| {
+        let key = match name.as_str() {
+            Some(name) => Key::try_from(name.to_string()),
+            None => Err(format!("'{}' is not a key name", name)),
+        };
+        key.map_err(|err| eprintln!("can't read controls: {}", err)).ok()
+    }).collect())
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            move_left: vec![Key(KeyCode::Left)],
+            move_right: vec![Key(KeyCode::Right)],
+            fire: vec![Key(KeyCode::Up)],
+            pause: vec![Key(KeyCode::Escape)],
+            confirm: vec![Key(KeyCode::Space), Key(KeyCode::Enter)],
+        }
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &Vec<Key> {
+        match action {
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::Fire => &self.fire,
+            Action::Pause => &self.pause,
+            Action::Confirm => &self.confirm,
+        }
+    }
+
+    fn keys_mut(&mut self, action: Action) -> &mut Vec<Key> {
+        match action {
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Fire => &mut self.fire,
+            Action::Pause => &mut self.pause,
+            Action::Confirm => &mut self.confirm,
+        }
+    }
+
+    // moves a key already bound to the action to the end, so it's dropped last
+    pub fn bind(&mut self, action: Action, key: Key) {
+        let keys = self.keys_mut(action);
+        keys.retain(|k| *k != key);
+        keys.push(key);
+        if keys.len() > MAX_BINDINGS {
+            keys.remove(0);
+        }
+    }
+
+    // the last key of an action always stays, so it can't get stuck unbound
+    pub fn unbind_last(&mut self, action: Action) {
+        let keys = self.keys_mut(action);
+        if keys.len() > 1 {
+            keys.pop();
+        }
+    }
+
+    // an action left without keys in the settings file gets its default ones back
+    pub fn fill_defaults(&mut self) {
+        let defaults = Bindings::default();
+        for action in Action::ALL {
+            if self.keys(action).is_empty() {
+                *self.keys_mut(action) = defaults.keys(action).clone();
+            }
+        }
+    }
+
+    // the key shown in prompts
+    pub fn key_name(&self, action: Action) -> &'static str {
+        self.keys(action).first().map_or("?", |key| key.name())
+    }
+
+    pub fn down(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|key| is_key_down(key.0))
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|key| is_key_pressed(key.0))
+    }
+
+    pub fn describe(&self, action: Action) -> String {
+        self.keys(action).iter().map(|key| key.name()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+// the keys held and pressed in this frame, as the simulation sees them
+pub fn from_keyboard(bindings: &Bindings) -> InputFrame {
+    InputFrame {
+        left: bindings.down(Action::MoveLeft),
+        right: bindings.down(Action::MoveRight),
+        fire: bindings.down(Action::Fire),
+        pause: bindings.pressed(Action::Pause),
+        confirm: bindings.pressed(Action::Confirm),
+    }
+}
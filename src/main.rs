@@ -1,40 +1,52 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use macroquad::prelude::*;
-extern crate rand;
-use rand::{Rng};
+use std::process;
+
+// the game itself lives in the sim crate, this is the window around it
+use sim::game::{Difficulty, GameMode, GameState};
+use sim::level::Campaign;
+use sim::replay::{Recorder, Replay};
+use sim::world::World;
+use sim::highscores::{self, HighScore, HighScores, InitialsEntry};
+use sim::input::InputFrame;
+
+mod options;
+use options::Options;
+
+mod assets;
+use assets::{AssetError, Assets};
 
 mod resources;
 use resources::Resources;
 
-mod player;
-use player::Player;
-
-mod game;
-use game::Game;
+mod audio;
+use audio::Audio;
 
-mod enemy;
-use enemy::Enemy;
+mod sprites;
+mod render;
+mod canvas;
+use canvas::Canvas;
 
-mod block;
-use block::Block;
+mod editor;
+use editor::Editor;
 
-mod bullet;
-use bullet::Bullet;
+mod input;
+use input::Action;
 
-mod bomb;
-use bomb::Bomb;
+mod settings;
+use settings::Settings;
 
-mod ufo;
-use ufo::Ufo;
+mod menu;
+use menu::{Menu, MenuAction, Screen};
 
-fn window_conf() -> Conf {
+fn window_conf(fullscreen: bool) -> Conf {
     let mut title = String::from("Space Invaders v");
     title.push_str(env!("CARGO_PKG_VERSION"));
     Conf {
         window_title: title
         .to_owned(),
-        fullscreen: false,
+        fullscreen,
         sample_count: 16,
         window_width: resources::WINDOW_WIDTH,
         window_height: resources::WINDOW_HEIGHT,
@@ -42,502 +54,291 @@ fn window_conf() -> Conf {
     }
 }
 
-pub enum GameState {
-    Intro,
-    InitLevel,
-    Game,
-    LevelFail,
-    Paused,
-    GameOver,
-    LevelCompleted,
+fn load_replay(path: &str) -> Replay {
+    Replay::load(path).unwrap_or_else(|err| {
+        eprintln!("can't load replay '{}': {}", path, err);
+        process::exit(1);
+    })
 }
 
-async fn make_blocks_array(resources: &Resources) -> Vec<Block> {
-    let mut blocks: Vec<Block> = Vec::new();
-
-    for y in 40..=45 {
-        for x in 10..=20 {
-            blocks.push(
-                Block::new((x * 10) as f32, (y * 10) as f32, resources).await,
-            );
-        }
-
-        for x in 30..=40 {
-            blocks.push(
-                Block::new((x * 10) as f32, (y * 10) as f32, resources).await,
-            );
-        }
-
-        for x in 50..=60 {
-            blocks.push(
-                Block::new((x * 10) as f32, (y * 10) as f32, resources).await,
-            );
-        }
+// a replay only plays out the same on the missions it was recorded with
+fn check_replay(replay: &Replay, campaign: &Campaign) {
+    if replay.campaign != campaign.fingerprint() {
+        eprintln!("can't play the replay: it was recorded with different {} levels", replay.mode.name());
+        process::exit(1);
     }
-
-    return blocks
 }
 
-async fn make_enemies_array() -> Vec<Enemy> {
-    let mut enemies: Vec<Enemy> = Vec::new();
-
-    let mut x: f32;
-    for i in (80..=600).step_by(70) {
-        x = i as f32;
-        enemies.push(
-            Enemy::new(x, 80.0, "e").await,
-        );
-    }
+fn load_campaign(assets: &Assets, mode: GameMode) -> Campaign {
+    Campaign::load(assets, mode).unwrap_or_else(|err| {
+        eprintln!("can't load the {} campaign: {}", mode.name(), err);
+        process::exit(1);
+    })
+}
 
-    for i in (80..=600).step_by(70) {
-        x = i as f32;
-        enemies.push(
-            Enemy::new(x, 130.0, "a").await,
-        );
-    }
+// every mode's campaign, a broken one is reported and replaced by the embedded copy
+fn load_campaigns(assets: &Assets, errors: &mut Vec<AssetError>) -> Vec<Campaign> {
+    GameMode::ALL.iter().map(|&mode| {
+        Campaign::load(assets, mode).unwrap_or_else(|err| {
+            errors.push(err.into());
+            load_campaign(&Assets::embedded(), mode)
+        })
+    }).collect()
+}
 
-    for i in (80..=600).step_by(70) {
-        x = i as f32;
-        enemies.push(
-            Enemy::new(x, 180.0, "b").await,
-        );
+// plays a replay back without a window and prints how the run went
+fn run_headless(assets: &Assets, replay: Replay) {
+    let campaign = load_campaign(assets, replay.mode);
+    check_replay(&replay, &campaign);
+    let mut world = World::new(replay.seed, replay.mode, campaign)
+        .with_difficulty(replay.difficulty);
+    // the same best score the windowed game starts with
+    world.game.hi_score = HighScores::load().best(replay.mode);
+    let mut state = world.state;
+
+    for (frame, (dt, input)) in replay.frames.into_iter().enumerate() {
+        world.step(dt, input);
+        if world.state != state {
+            state = world.state;
+            println!("frame {}: {:?}", frame, state);
+        }
     }
 
-    return enemies
+    println!("seed: {}, score: {}, hi-score: {}, mission: {}, state: {:?}",
+        world.seed, world.game.score, world.game.hi_score, world.game.mission, world.state);
 }
 
-fn draw_info(font: Font, score: &str, hi_score: &str, lives: &str) {
-    draw_line(0.0, 525.0, 700.0, 525.0, 1.0, BROWN);
-    
-    draw_text_ex("SCORE: ", 30.0, 545.0, 
-        TextParams {
-            font,
-            font_size: 25,
-            color: WHITE,
-            ..Default::default()
-        },
-    );
-
-    draw_text_ex(score, 155.0, 545.0, 
-        TextParams {
-            font,
-            font_size: 25,
-            color: ORANGE,
-            ..Default::default()
-        },
-    );
-
-    draw_text_ex("LIVES: ", 260.0, 545.0, 
-        TextParams {
-            font,
-            font_size: 25,
-            color: WHITE,
-            ..Default::default()
-        },
-    );
-
-    draw_text_ex(lives, 375.0, 545.0, 
-        TextParams {
-            font,
-            font_size: 25,
-            color: ORANGE,
-            ..Default::default()
-        },
-    );
-
-    draw_text_ex("HI-SCORE: ", 450.0, 545.0, 
-        TextParams {
-            font,
-            font_size: 25,
-            color: WHITE,
-            ..Default::default()
-        },
-    );
-
-    draw_text_ex(hi_score, 620.0, 545.0, 
-        TextParams {
-            font,
-            font_size: 25,
-            color: ORANGE,
-            ..Default::default()
-        },
-    );
+fn campaign_for(campaigns: &[Campaign], mode: GameMode) -> &Campaign {
+    &campaigns[GameMode::ALL.iter().position(|m| *m == mode).unwrap()]
 }
 
-fn show_text(font: Font, header_text: &str, message_text: &str) {
-    let header_dims = measure_text(header_text, Some(font), 50, 1.0);
-    let message_dims = measure_text(message_text, Some(font), 20, 1.0);
-
-    draw_text_ex(
-        &header_text,
-        screen_width() * 0.5 - header_dims.width * 0.5,
-        240.0,
-        TextParams {
-            font,
-            font_size: 50,
-            color: WHITE,
-            ..Default::default()
-        },
-    );
-
-    draw_text_ex(
-        &message_text,
-        screen_width() * 0.5 - message_dims.width * 0.5,
-        280.0,
-        TextParams {
-            font,
-            font_size: 20,
-            color: WHITE,
-            ..Default::default()
-        },
-    );
+fn new_world(campaigns: &[Campaign], seed: u64, mode: GameMode, difficulty: Difficulty, high_scores: &HighScores) -> World {
+    let campaign = campaign_for(campaigns, mode).clone();
+    let mut world = World::new(seed, mode, campaign).with_difficulty(difficulty);
+    world.game.hi_score = high_scores.best(mode);
+    world
 }
 
-pub enum Dir {
-    Left,
-    Right,
+fn save_settings(settings: &Settings) {
+    if let Err(err) = settings.save() {
+        eprintln!("can't save settings: {}", err);
+    }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    let resources = Resources::new().await;
-    let mut game_state = GameState::Intro;
-    let mut player = Player::new(&resources).await;
-    let mut enemies: Vec<Enemy> = make_enemies_array().await;
-    let mut blocks: Vec<Block> = make_blocks_array(&resources).await;
-    let mut game = Game::new().await;
-    let mut enemy_direction: Dir = Dir::Left;
-    let mut bullets: Vec<Bullet> = Vec::new();
-    let mut ufo: Vec<Ufo> = Vec::new();
-    let mut bombs: Vec<Bomb> = Vec::new();
-    let mut bomb_last_time: f64 = get_time();
-    let mut time_between_bombs: f64;
-    let mut ufo_last_time: f64 = get_time();
-    let mut next_bonus_at: i32 = 1000;
-
-    loop {
-        clear_background(BLACK);
-
-        match game_state {
-            GameState::Intro => {
-                game.score = 0;
-                game.lives = 3;
-                game.mission = 1;
-                game.enemy_speed = resources::ENEMY_INIT_SPEED;
-
-                draw_texture(resources.intro, 0.0, 0.0, WHITE);
-                if is_key_pressed(KeyCode::Space) {
-                    game_state = GameState::InitLevel;
-                }
-            },
-            GameState::InitLevel => {
-                bomb_last_time = get_time();
-                player.x = 320.0;
-                draw_info(resources.font, 
-                    game.score.to_string().as_str(), 
-                    game.hi_score.to_string().as_str(),
-                  game.lives.to_string().as_str());
-
-                for enemy in &mut enemies {
-                    enemy.draw();
-                }
-                for block in &mut blocks {
-                    block.draw();
-                }
-                
-                let mut header_text = String::from("MISSION - ");
-                header_text.push_str(&game.mission.to_string());
-                show_text(resources.font, header_text.as_str(), "press 'space' to start...");
-
-                if is_key_pressed(KeyCode::Space) {
-                    game_state = GameState::Game;
-                }
-            },
-            GameState::Game => {
-                draw_info(resources.font, 
-                          game.score.to_string().as_str(), 
-                          game.hi_score.to_string().as_str(),
-                        game.lives.to_string().as_str());
-                
-                for bomb in &mut bombs {
-                    bomb.update(get_frame_time());
-                    bomb.draw();
-
-                    if let Some(_i) = bomb.rect.intersect(player.rect) {
-                        bomb.destroyed = true;
-                        if game.lives > 0 {
-                            game_state = GameState::LevelFail;
-                        } else {
-                            game_state = GameState::GameOver;
-                        }
-                    }
-
-                    if bullets.len() > 0 {
-                        if let Some(_i) = bomb.rect.intersect(bullets[0].rect) {
-                            bomb.destroyed = true;
-                            bullets[0].destroyed = true;
-                            game.score += 5;
-                        }
-                    }
-                }
+async fn run(options: Options, mut settings: Settings) {
+    let assets = assets::open(options.assets_dir.clone());
+    let mut resources = Resources::new(&assets);
+    // loaded once, so nothing can fail later in the session
+    let campaigns = load_campaigns(&assets, &mut resources.errors);
+    let mut audio = Audio::load(&assets).await;
+    let mut canvas = Canvas::new(settings.integer_scaling);
+
+    // the game still runs on placeholders, but says what's broken first
+    if !resources.errors.is_empty() {
+        for err in &resources.errors {
+            eprintln!("asset error: {}", err);
+        }
+        while !settings.controls.pressed(Action::Confirm) {
+            canvas.begin();
+            render::draw_asset_errors(&resources, &settings.controls);
+            canvas.present();
+            next_frame().await
+        }
+        next_frame().await
+    }
 
-                if get_time() - ufo_last_time > resources::MINIMAL_TIME_BETWEEN_EACH_UFO {
-                    let from_side = match rand::thread_rng().gen_range(0..=1) {
-                        0 => "left",
-                        _ => "right",
-                    };
-                    ufo.push(
-                        Ufo::new(from_side).await,
-                    );
-                    ufo_last_time = get_time();
-                }
+    let mut fullscreen = settings.fullscreen;
+    if let Some(mode) = options.mode {
+        settings.mode = mode;
+    }
+    audio.set_volume(settings.master_volume, settings.sfx_volume);
+    if settings.muted {
+        audio.toggle_mute();
+    }
 
-                if ufo.len() > 0 {
-                    ufo[0].draw();
-                    if bullets.len() > 0 {
-                        if let Some(_i) = bullets[0].rect.intersect(ufo[0].rect) {
-                            ufo[0].destroyed = true;
-                            bullets[0].destroyed = true;
-                            game.score += 100;
-                        }
-                    }
-                }
+    let replay = options.replay.as_deref().map(load_replay);
+    let (mut seed, mode, difficulty) = match &replay {
+        Some(replay) => {
+            check_replay(replay, campaign_for(&campaigns, replay.mode));
+            (replay.seed, replay.mode, replay.difficulty)
+        },
+        None => (options.seed.unwrap_or_else(::rand::random), settings.mode, settings.difficulty),
+    };
+    let mut high_scores = HighScores::load();
+    let mut world = new_world(&campaigns, seed, mode, difficulty, &high_scores);
+    let mut initials: Option<InitialsEntry> = None;
+    let mut last_state = world.state;
+
+    // recording starts with the first game begun from the menu
+    let mut record_path = options.record;
+    let mut recorder: Option<Recorder> = None;
+    let mut replay_frames = replay.map(|replay| replay.frames.into_iter());
+    let mut menu: Option<Menu> = None;
+    // kept after leaving the editor, so nothing is lost until the game is closed
+    let mut editor: Option<Editor> = None;
+    let mut editing = false;
 
-                // generate random time between the bombs
-                if get_time() - bomb_last_time > resources::MINIMAL_TIME_BETWEEN_BOMBS {
-                    time_between_bombs = rand::thread_rng().gen_range(0.0..=40.0);
-
-                    if enemies.len() > 0 {
-                        if get_time() - bomb_last_time > resources::MINIMAL_TIME_BETWEEN_BOMBS + time_between_bombs {
-                            let bomb_type = match rand::thread_rng().gen_range(0..=1) {
-                                0 => "a",
-                                _ => "b",
-                            };
-                            let enemy_index = rand::thread_rng().gen_range(0..enemies.len());
-                            bombs.push(
-                                Bomb::new(enemies[enemy_index].x + 25.0, enemies[enemy_index].y + 36.0, bomb_type.to_string().as_str()).await,
-                            );
-                            bomb_last_time = get_time();
-                        }
-                    }
-                }
-                if is_key_pressed(KeyCode::Escape) {
-                    game_state = GameState::Paused;
-                }
-                
-                if is_key_down(KeyCode::Up) {
-                    if bullets.len() == 0 {
-                        bullets.push(
-                            Bullet::new(player.x + 32.0, player.y, &resources).await,
-                        );
-                    }
-                }
-                player.draw();
+    loop {
+        if is_key_pressed(KeyCode::F11) {
+            settings.fullscreen = !settings.fullscreen;
+            save_settings(&settings);
+        }
+        if is_key_pressed(KeyCode::F10) {
+            settings.integer_scaling = !settings.integer_scaling;
+            save_settings(&settings);
+        }
+        if settings.fullscreen != fullscreen {
+            fullscreen = settings.fullscreen;
+            set_fullscreen(fullscreen);
+        }
+        canvas.set_integer_scaling(settings.integer_scaling);
 
-                if bullets.len() > 0 {
-                    bullets[0].update(get_frame_time());
-                    bullets[0].draw();
-                }
+        if let (true, Some(open)) = (editing, &mut editor) {
+            clear_background(BLACK);
+            editing = open.update(&assets, &resources, &canvas, &settings.controls, &mut audio);
+            next_frame().await;
+            continue;
+        }
 
-                let mut need_to_pull_down: bool = false;
-                for enemy in &mut enemies {
-                    match enemy_direction {
-                        Dir::Left => {
-                            enemy.x -= 1.0 * game.enemy_speed;
-                            if enemy.x < 0.0 {
-                                need_to_pull_down = true;
-                                enemy_direction = Dir::Right;
-                            }
-                        },
-                        Dir::Right => {
-                            enemy.x += 1.0 * game.enemy_speed;
-                            if enemy.x > 650.0 {
-                                need_to_pull_down = true;
-                                enemy_direction = Dir::Left;
-                            }
-                        },
-                    }
-                    
-                    enemy.draw();
-
-                    if enemy.y + 30.0 > player.y {
-                        game_state = GameState::GameOver;
-                    } 
-
-                    if bullets.len() > 0 {
-                        if let Some(_i) = bullets[0].rect.intersect(enemy.rect) {
-                            enemy.destroyed = true;
-                            bullets[0].destroyed = true;
-                            game.score += 10;
-                        }
-                    }
+        let mut audio_changed = false;
+        if is_key_pressed(KeyCode::M) {
+            audio.toggle_mute();
+            audio_changed = true;
+        }
+        if is_key_pressed(KeyCode::Minus) {
+            audio.set_volume(audio.master_volume - 0.1, audio.sfx_volume);
+            audio_changed = true;
+        }
+        if is_key_pressed(KeyCode::Equal) {
+            audio.set_volume(audio.master_volume + 0.1, audio.sfx_volume);
+            audio_changed = true;
+        }
+        if audio_changed {
+            settings.master_volume = audio.master_volume;
+            settings.muted = audio.muted;
+            save_settings(&settings);
+        }
 
-                    if let Some(_i) = player.rect.intersect(enemy.rect) {
-                        game_state = GameState::GameOver;
-                    }
+        // the world waits while initials are entered, so replays never contain this screen
+        if let Some(entry) = &mut initials {
+            if settings.controls.pressed(Action::MoveLeft) {
+                entry.prev_letter();
+            }
+            if settings.controls.pressed(Action::MoveRight) {
+                entry.next_letter();
+            }
+            if settings.controls.pressed(Action::Confirm) && entry.confirm() {
+                high_scores.insert(HighScore {
+                    score: world.game.score,
+                    initials: entry.initials(),
+                    mission: world.game.mission,
+                    date: highscores::today(),
+                    mode: world.game.mode.name().to_string(),
+                });
+                if let Err(err) = high_scores.save() {
+                    eprintln!("can't save high scores: {}", err);
                 }
-
-                for block in &mut blocks {
-                    for enemy in &mut enemies {
-                        if let Some(_i) = enemy.rect.intersect(block.rect) {
-                            block.destroyed = true;
-                        }
-                    }
-                    if bullets.len() > 0 {
-                        if let Some(_i) = bullets[0].rect.intersect(block.rect) {
-                            block.destroyed = true;
-                            bullets[0].destroyed = true;
+                initials = None;
+            }
+        } else {
+            // replays play by themselves, without menus
+            let root = Screen::for_state(world.state).filter(|_| replay_frames.is_none());
+            if root != menu.as_ref().map(Menu::root) {
+                menu = root.map(Menu::new);
+            }
+
+            // while a menu is up, the world only sees what was chosen in it
+            let mut input = input::from_keyboard(&settings.controls);
+            if let Some(open) = &mut menu {
+                input = InputFrame::default();
+                match open.update(&mut settings) {
+                    Some(MenuAction::Start) => {
+                        seed = options.seed.unwrap_or_else(::rand::random);
+                        world = new_world(&campaigns, seed, settings.mode, settings.difficulty, &high_scores);
+                        recorder = record_path.take().and_then(|path| {
+                            Recorder::create(&path, seed, settings.mode, settings.difficulty, world.campaign.fingerprint()).map_err(|err| {
+                                eprintln!("can't create replay '{}': {}", path, err);
+                            }).ok()
+                        });
+                        input.confirm = true;
+                    },
+                    Some(MenuAction::Resume) | Some(MenuAction::PlayAgain) => input.confirm = true,
+                    Some(MenuAction::MainMenu) => {
+                        if recorder.take().is_some() {
+                            println!("replay recording finished");
                         }
-                    }
-                    for bomb in &mut bombs {
-                        if let Some(_i) = bomb.rect.intersect(block.rect) {
-                            bomb.destroyed = true;
-                            block.destroyed = true;
-                        }
-                    }
+                        audio.stop_loops();
+                        world = new_world(&campaigns, seed, settings.mode, settings.difficulty, &high_scores);
+                    },
+                    Some(MenuAction::Editor) => {
+                        editor.get_or_insert_with(|| Editor::new(world.level().clone(), settings.mode, seed, &campaigns));
+                        editing = true;
+                    },
+                    Some(MenuAction::Quit) => break,
+                    Some(MenuAction::ModeChanged) => {
+                        world = new_world(&campaigns, seed, settings.mode, settings.difficulty, &high_scores);
+                        save_settings(&settings);
+                    },
+                    Some(MenuAction::SettingsChanged) => {
+                        audio.set_volume(settings.master_volume, settings.sfx_volume);
+                        save_settings(&settings);
+                    },
+                    None => {},
                 }
-
-                if need_to_pull_down {
-                    for enemy in &mut enemies {
-                        enemy.y += 10.0;
+            }
+
+            // a finished replay leaves the last frame on screen
+            let frame = match &mut replay_frames {
+                Some(frames) => frames.next(),
+                None => Some((get_frame_time(), input)),
+            };
+
+            if let Some((dt, input)) = frame {
+                if let Some(rec) = &mut recorder {
+                    if let Err(err) = rec.record(dt, input) {
+                        eprintln!("replay recording stopped: {}", err);
+                        recorder = None;
                     }
-                    game.enemy_speed += 0.2;
-                }
-
-                for block in &mut blocks {
-                    block.draw();
-                }
-
-                if game.score > game.hi_score {
-                    game.hi_score = game.score;
-                }
-
-                if game.score > next_bonus_at {
-                    game.lives += 1;
-                    next_bonus_at += 1000;
                 }
+                world.step(dt, input);
+                audio.update(&world, dt);
+            }
+
+            if world.state == GameState::GameOver && last_state != GameState::GameOver
+                && replay_frames.is_none() && high_scores.qualifies(world.game.score) {
+                initials = Some(InitialsEntry::new());
+            }
+            last_state = world.state;
+        }
 
-                if enemies.len() == 0 {
-                    game_state = GameState::LevelCompleted;
-                }
-            },
-            GameState::LevelFail => {
-                draw_info(resources.font, 
-                    game.score.to_string().as_str(), 
-                    game.hi_score.to_string().as_str(),
-                  game.lives.to_string().as_str());
-
-                for enemy in &mut enemies {
-                    enemy.draw();
-                }
-                for block in &mut blocks {
-                    block.draw();
-                }
-                show_text(resources.font, "MISSION FAIL", "press 'space' to continue...");
-                if is_key_pressed(KeyCode::Space) {
-                    game.lives -= 1;
-                    player.x = 320.0;
-                    bullets.clear();
-                    bombs.clear();
-                    ufo.clear();
-                    game_state = GameState::Game;
-                }
-            },
-            GameState::Paused => {
-                show_text(resources.font, "GAME PAUSED", "press 'space' to continue...");
-                if is_key_pressed(KeyCode::Space) {
-                    game_state = GameState::Game;
-                }
-            },
-            GameState::LevelCompleted => {
-                draw_info(resources.font, 
-                    game.score.to_string().as_str(), 
-                    game.hi_score.to_string().as_str(),
-                  game.lives.to_string().as_str());
-                for block in &mut blocks {
-                    block.draw();
-                }
-                show_text(resources.font, "MISSION COMPLETED", "press 'space' to continue...");
-                if is_key_pressed(KeyCode::Space) {
-                    player.x = 320.0;
-                    bullets.clear();
-                    bombs.clear();
-                    enemies.clear();
-                    ufo.clear();
-                    enemies = make_enemies_array().await;
-                    blocks.clear();
-                    blocks = make_blocks_array(&resources).await;
-                    game.mission += 1;
-                    game.enemy_speed = resources::ENEMY_INIT_SPEED + game.mission as f32 * 0.2;
-                    game_state = GameState::InitLevel;
-                }
-            },
-            GameState::GameOver => {
-                draw_info(resources.font, 
-                    game.score.to_string().as_str(), 
-                    game.hi_score.to_string().as_str(),
-                  game.lives.to_string().as_str());
-
-                for enemy in &mut enemies {
-                    enemy.draw();
-                }
-                for block in &mut blocks {
-                    block.draw();
-                }
-                show_text(resources.font, "GAME OVER", "press 'space' to start new game...");
-                if is_key_pressed(KeyCode::Space) {
-                    bullets.clear();
-                    bombs.clear();
-                    ufo.clear();
-                    blocks.clear();
-                    blocks = make_blocks_array(&resources).await;
-                    enemies.clear();
-                    enemies = make_enemies_array().await;
-                    game.score = 0;
-                    game.lives = 3;
-                    game.mission = 1;
-                    game.enemy_speed = resources::ENEMY_INIT_SPEED;
-                    game_state = GameState::InitLevel;
+        canvas.begin();
+        match &initials {
+            Some(entry) => render::draw_initials_entry(&resources, entry, world.game.score, &settings.controls),
+            None => {
+                render::draw_world(&world, &resources, &settings.controls);
+                if let Some(open) = &menu {
+                    render::draw_menu(&resources, open, &settings, &high_scores);
                 }
             },
         }
+        canvas.present();
 
-        // GC
-        match enemies.iter().position(|x| x.destroyed == true) {
-            Some(idx) => {
-                enemies.remove(idx);
-            },
-            None => {},
-        };
-
-        match blocks.iter().position(|x| x.destroyed == true) {
-            Some(idx) => {
-                blocks.remove(idx);
-            },
-            None => {},
-        };
-
-        match bullets.iter().position(|x| x.destroyed == true) {
-            Some(idx) => {
-                bullets.remove(idx);
-            },
-            None => {},
-        };
+        next_frame().await
+    }
+}
 
-        match bombs.iter().position(|x| x.destroyed == true) {
-            Some(idx) => {
-                bombs.remove(idx);
-            },
-            None => {},
-        };
+fn main() {
+    let options = Options::from_args();
 
-        match ufo.iter().position(|x| x.destroyed == true) {
-            Some(idx) => {
-                ufo.remove(idx);
-            },
-            None => {},
-        };
+    if options.check_assets {
+        process::exit(assets::check_all(&assets::open(options.assets_dir.clone())));
+    }
 
-        next_frame().await
+    if options.headless {
+        run_headless(&assets::open(options.assets_dir.clone()), load_replay(options.replay.as_deref().unwrap()));
+        return;
     }
-}
\ No newline at end of file
+
+    let settings = Settings::load();
+    macroquad::Window::from_config(window_conf(settings.fullscreen), run(options, settings));
+}
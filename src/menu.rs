@@ -0,0 +1,314 @@
+use macroquad::prelude::*;
+
+use sim::game::{Difficulty, GameMode, GameState};
+
+use crate::input::{Action, Bindings, Key};
+use crate::settings::Settings;
+
+const VOLUME_STEP: f32 = 0.1;
+
+// keys taken by the mute and volume shortcuts, they can't be bound
+const RESERVED_KEYS: [KeyCode; 3] = [KeyCode::M, KeyCode::Minus, KeyCode::Equal];
+// cancel waiting for a key, so they can't be bound either
+const CANCEL_KEYS: [KeyCode; 2] = [KeyCode::Backspace, KeyCode::Delete];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Screen {
+    Main,
+    Options,
+    Controls,
+    HighScores,
+    Pause,
+    GameOver,
+    // over the prompts between missions and lives, the world shows what happened
+    MissionStart,
+    MissionCompleted,
+    LifeLost,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Item {
+    Start,
+    Mode,
+    HighScores,
+    Options,
+    Editor,
+    Quit,
+    MasterVolume,
+    SfxVolume,
+    Controls,
+    Fullscreen,
+    IntegerScaling,
+    Difficulty,
+    Back,
+    Resume,
+    Continue,
+    PlayAgain,
+    MainMenu,
+    Bind(Action),
+    ResetControls,
+}
+
+// what the game loop has to do about a choice
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuAction {
+    Start,
+    Resume,
+    PlayAgain,
+    MainMenu,
+    Editor,
+    Quit,
+    ModeChanged,
+    SettingsChanged,
+}
+
+impl Screen {
+    // the menu shown over a state of the world, none while playing
+    pub fn for_state(state: GameState) -> Option<Self> {
+        match state {
+            GameState::Intro => Some(Screen::Main),
+            GameState::InitLevel => Some(Screen::MissionStart),
+            GameState::Game => None,
+            GameState::LevelFail => Some(Screen::LifeLost),
+            GameState::Paused => Some(Screen::Pause),
+            GameState::LevelCompleted => Some(Screen::MissionCompleted),
+            GameState::GameOver => Some(Screen::GameOver),
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Screen::Main => "",
+            Screen::Options => "OPTIONS",
+            Screen::Controls => "CONTROLS",
+            Screen::HighScores => "HIGH SCORES",
+            Screen::Pause => "GAME PAUSED",
+            Screen::GameOver => "GAME OVER",
+            Screen::MissionStart | Screen::MissionCompleted | Screen::LifeLost => "",
+        }
+    }
+
+    // screens without items only show something and go back on any confirm
+    pub fn items(self) -> &'static [Item] {
+        match self {
+            Screen::Main => &[Item::Start, Item::Mode, Item::HighScores, Item::Options, Item::Editor, Item::Quit],
+            Screen::Options => &[
+                Item::MasterVolume,
+                Item::SfxVolume,
+                Item::Controls,
+                Item::Fullscreen,
+                Item::IntegerScaling,
+                Item::Difficulty,
+                Item::Back,
+            ],
+            Screen::Controls => &[
+                Item::Bind(Action::MoveLeft),
+                Item::Bind(Action::MoveRight),
+                Item::Bind(Action::Fire),
+                Item::Bind(Action::Pause),
+                Item::Bind(Action::Confirm),
+                Item::ResetControls,
+                Item::Back,
+            ],
+            Screen::HighScores => &[],
+            Screen::Pause => &[Item::Resume, Item::Options, Item::MainMenu],
+            Screen::GameOver => &[Item::PlayAgain, Item::HighScores, Item::MainMenu],
+            Screen::MissionStart | Screen::MissionCompleted => &[Item::Continue, Item::Options, Item::MainMenu],
+            // the explosion ends by itself, continuing only cuts it short
+            Screen::LifeLost => &[Item::Continue],
+        }
+    }
+}
+
+fn percent(volume: f32) -> i32 {
+    (volume * 100.0).round() as i32
+}
+
+// the next or previous entry of a list, wrapping around
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, dir: i32) -> T {
+    let pos = all.iter().position(|v| *v == current).unwrap_or(0) as i32;
+    all[(pos + dir).rem_euclid(all.len() as i32) as usize]
+}
+
+impl Item {
+    pub fn label(self, settings: &Settings) -> String {
+        let muted = if settings.muted { " (MUTED)" } else { "" };
+        match self {
+            Item::Start => "START".to_string(),
+            Item::Mode => format!("MODE: {}", settings.mode.name().to_uppercase()),
+            Item::HighScores => "HIGH SCORES".to_string(),
+            Item::Options => "OPTIONS".to_string(),
+            Item::Editor => "LEVEL EDITOR".to_string(),
+            Item::Quit => "QUIT".to_string(),
+            Item::MasterVolume => format!("VOLUME: {}%{}", percent(settings.master_volume), muted),
+            Item::SfxVolume => format!("EFFECTS: {}%", percent(settings.sfx_volume)),
+            Item::Controls => "CONTROLS".to_string(),
+            Item::Fullscreen => format!("FULLSCREEN: {}", if settings.fullscreen { "ON" } else { "OFF" }),
+            Item::IntegerScaling => format!("INTEGER SCALING: {}", if settings.integer_scaling { "ON" } else { "OFF" }),
+            Item::Difficulty => format!("DIFFICULTY: {}", settings.difficulty.name().to_uppercase()),
+            Item::Back => "BACK".to_string(),
+            Item::Resume => "RESUME".to_string(),
+            Item::Continue => "CONTINUE".to_string(),
+            Item::PlayAgain => "PLAY AGAIN".to_string(),
+            Item::MainMenu => "MAIN MENU".to_string(),
+            Item::Bind(action) => format!("{}: {}", action.name(), settings.controls.describe(action)).to_uppercase(),
+            Item::ResetControls => "RESET TO DEFAULTS".to_string(),
+        }
+    }
+}
+
+// keyboard driven menus, one screen on top of the other
+pub struct Menu {
+    screens: Vec<Screen>,
+    pub selected: usize,
+    // the action waiting for a key to be bound to it
+    pub binding: Option<Action>,
+}
+
+impl Menu {
+    pub fn new(root: Screen) -> Self {
+        Self {
+            screens: vec![root],
+            selected: 0,
+            binding: None,
+        }
+    }
+
+    pub fn root(&self) -> Screen {
+        self.screens[0]
+    }
+
+    pub fn screen(&self) -> Screen {
+        *self.screens.last().unwrap()
+    }
+
+    fn open(&mut self, screen: Screen) {
+        self.screens.push(screen);
+        self.selected = 0;
+    }
+
+    // leaving the pause menu resumes the game, the other roots stay
+    fn back(&mut self) -> Option<MenuAction> {
+        if self.screens.len() > 1 {
+            self.screens.pop();
+            self.selected = 0;
+            None
+        } else if self.root() == Screen::Pause {
+            Some(MenuAction::Resume)
+        } else {
+            None
+        }
+    }
+
+    // backspace or delete cancels, the reserved keys and ones without a name are ignored
+    fn capture(&mut self, action: Action, settings: &mut Settings) -> Option<MenuAction> {
+        if CANCEL_KEYS.iter().any(|&code| is_key_pressed(code)) {
+            self.binding = None;
+            return None;
+        }
+
+        let key = get_last_key_pressed()
+            .filter(|code| !RESERVED_KEYS.contains(code))
+            .and_then(Key::from_code)?;
+        settings.controls.bind(action, key);
+        self.binding = None;
+        Some(MenuAction::SettingsChanged)
+    }
+
+    // arrows, enter and escape always work in menus, whatever is bound
+    pub fn update(&mut self, settings: &mut Settings) -> Option<MenuAction> {
+        if let Some(action) = self.binding {
+            return self.capture(action, settings);
+        }
+
+        let controls = &settings.controls;
+        let confirm = is_key_pressed(KeyCode::Enter) || controls.pressed(Action::Confirm);
+        let left = is_key_pressed(KeyCode::Left) || controls.pressed(Action::MoveLeft);
+        let right = is_key_pressed(KeyCode::Right) || controls.pressed(Action::MoveRight);
+        if is_key_pressed(KeyCode::Escape) || controls.pressed(Action::Pause) {
+            return self.back();
+        }
+
+        let items = self.screen().items();
+        if items.is_empty() {
+            return if confirm { self.back() } else { None };
+        }
+
+        if is_key_pressed(KeyCode::Up) {
+            self.selected = (self.selected + items.len() - 1) % items.len();
+        }
+        if is_key_pressed(KeyCode::Down) {
+            self.selected = (self.selected + 1) % items.len();
+        }
+
+        let item = items[self.selected.min(items.len() - 1)];
+        if left {
+            return self.change(item, settings, -1);
+        }
+        if right {
+            return self.change(item, settings, 1);
+        }
+        if let Item::Bind(action) = item {
+            if is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
+                settings.controls.unbind_last(action);
+                return Some(MenuAction::SettingsChanged);
+            }
+        }
+        if confirm {
+            return self.activate(item, settings);
+        }
+        None
+    }
+
+    // left/right on a setting
+    fn change(&mut self, item: Item, settings: &mut Settings, dir: i32) -> Option<MenuAction> {
+        match item {
+            Item::Mode => {
+                settings.mode = cycle(&GameMode::ALL, settings.mode, dir);
+                return Some(MenuAction::ModeChanged);
+            },
+            Item::Difficulty => settings.difficulty = cycle(&Difficulty::ALL, settings.difficulty, dir),
+            Item::MasterVolume => settings.master_volume = (settings.master_volume + dir as f32 * VOLUME_STEP).clamp(0.0, 1.0),
+            Item::SfxVolume => settings.sfx_volume = (settings.sfx_volume + dir as f32 * VOLUME_STEP).clamp(0.0, 1.0),
+            Item::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            Item::IntegerScaling => settings.integer_scaling = !settings.integer_scaling,
+            _ => return None,
+        }
+        Some(MenuAction::SettingsChanged)
+    }
+
+    fn activate(&mut self, item: Item, settings: &mut Settings) -> Option<MenuAction> {
+        match item {
+            Item::Start => Some(MenuAction::Start),
+            Item::HighScores => {
+                self.open(Screen::HighScores);
+                None
+            },
+            Item::Options => {
+                self.open(Screen::Options);
+                None
+            },
+            Item::Controls => {
+                self.open(Screen::Controls);
+                None
+            },
+            Item::Editor => Some(MenuAction::Editor),
+            Item::Quit => Some(MenuAction::Quit),
+            Item::Back => self.back(),
+            Item::Resume | Item::Continue => Some(MenuAction::Resume),
+            Item::PlayAgain => Some(MenuAction::PlayAgain),
+            Item::MainMenu => Some(MenuAction::MainMenu),
+            Item::Bind(action) => {
+                self.binding = Some(action);
+                None
+            },
+            Item::ResetControls => {
+                settings.controls = Bindings::default();
+                Some(MenuAction::SettingsChanged)
+            },
+            Item::Mode | Item::Difficulty | Item::MasterVolume | Item::SfxVolume | Item::Fullscreen | Item::IntegerScaling => {
+                self.change(item, settings, 1)
+            },
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::env;
+use std::process;
+
+use sim::game::GameMode;
+
+// command line options
+pub struct Options {
+    pub seed: Option<u64>,
+    // the one from the settings when not given
+    pub mode: Option<GameMode>,
+    pub record: Option<String>,
+    pub replay: Option<String>,
+    pub headless: bool,
+    pub assets_dir: Option<String>,
+    pub check_assets: bool,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: space-invaders [--mode classic|arcade] [--seed <number>] [--record <file>] [--replay <file> [--headless]] [--assets-dir <dir>] [--check-assets]");
+    process::exit(2);
+}
+
+impl Options {
+    pub fn from_args() -> Self {
+        let mut options = Self {
+            seed: None,
+            mode: None,
+            record: None,
+            replay: None,
+            headless: false,
+            assets_dir: None,
+            check_assets: false,
+        };
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().unwrap_or_else(|| usage());
+                    options.seed = Some(value.parse().unwrap_or_else(|_| usage()));
+                },
+                "--mode" => {
+                    let value = args.next().unwrap_or_else(|| usage());
+                    options.mode = Some(GameMode::from_name(&value).unwrap_or_else(|| usage()));
+                },
+                "--record" => {
+                    options.record = Some(args.next().unwrap_or_else(|| usage()));
+                },
+                "--replay" => {
+                    options.replay = Some(args.next().unwrap_or_else(|| usage()));
+                },
+                "--headless" => {
+                    options.headless = true;
+                },
+                "--assets-dir" => {
+                    options.assets_dir = Some(args.next().unwrap_or_else(|| usage()));
+                },
+                "--check-assets" => {
+                    options.check_assets = true;
+                },
+                _ => usage(),
+            }
+        }
+
+        if options.headless && options.replay.is_none() {
+            usage();
+        }
+
+        options
+    }
+}
@@ -0,0 +1,534 @@
+use macroquad::prelude::*;
+
+use sim::bunker::BUNKER_PIXEL;
+use sim::game::GameState;
+use sim::highscores::{HighScores, InitialsEntry};
+use sim::powerup::{self, PowerUpKind};
+use sim::world::{self, World};
+
+use crate::resources::Resources;
+use crate::sprites::SpriteKey;
+use crate::input::{Action, Bindings};
+use crate::menu::{Item, Menu, Screen};
+use crate::settings::Settings;
+
+// the line between the playfield and the score
+const HUD_LINE_Y: f32 = 525.0;
+
+// top of the title picture, above the line that used to ask for a key
+const INTRO_HEIGHT: f32 = 330.0;
+
+// one per kind, in the order of BombKind
+const BOMB_TINTS: [Color; 4] = [
+    Color::new(1.0, 1.0, 1.0, 1.0),
+    Color::new(1.0, 1.0, 1.0, 1.0),
+    Color::new(1.0, 0.5, 0.45, 1.0),
+    Color::new(0.55, 1.0, 0.55, 1.0),
+];
+
+// debris, one per kind in the order of EffectKind
+const EFFECT_COLORS: [Color; 5] = [
+    Color::new(1.0, 1.0, 1.0, 1.0),
+    Color::new(0.0, 0.89, 0.19, 1.0),
+    Color::new(1.0, 0.63, 0.0, 1.0),
+    Color::new(0.9, 0.16, 0.22, 1.0),
+    Color::new(1.0, 0.8, 0.2, 1.0),
+];
+
+// capsules, one per kind in the order of PowerUpKind
+const POWER_UP_COLORS: [Color; 6] = [
+    Color::new(1.0, 0.63, 0.0, 1.0),
+    Color::new(0.0, 0.47, 0.95, 1.0),
+    Color::new(0.78, 0.48, 1.0, 1.0),
+    Color::new(0.4, 0.75, 1.0, 1.0),
+    Color::new(0.0, 0.89, 0.19, 1.0),
+    Color::new(0.9, 0.16, 0.22, 1.0),
+];
+
+fn draw_info(font: Font, score: &str, hi_score: &str, lives: &str, power_ups: &str) {
+    draw_line(0.0, HUD_LINE_Y, world::FIELD_WIDTH, HUD_LINE_Y, 1.0, BROWN);
+
+    draw_text_ex(power_ups, 30.0, 518.0, 
+        TextParams {
+            font,
+            font_size: 18,
+            color: SKYBLUE,
+            ..Default::default()
+        },
+    );
+    
+    draw_text_ex("SCORE: ", 30.0, 545.0, 
+        TextParams {
+            font,
+            font_size: 25,
+            color: WHITE,
+            ..Default::default()
+        },
+    );
+
+    draw_text_ex(score, 155.0, 545.0, 
+        TextParams {
+            font,
+            font_size: 25,
+            color: ORANGE,
+            ..Default::default()
+        },
+    );
+
+    draw_text_ex("LIVES: ", 260.0, 545.0, 
+        TextParams {
+            font,
+            font_size: 25,
+            color: WHITE,
+            ..Default::default()
+        },
+    );
+
+    draw_text_ex(lives, 375.0, 545.0, 
+        TextParams {
+            font,
+            font_size: 25,
+            color: ORANGE,
+            ..Default::default()
+        },
+    );
+
+    draw_text_ex("HI-SCORE: ", 450.0, 545.0, 
+        TextParams {
+            font,
+            font_size: 25,
+            color: WHITE,
+            ..Default::default()
+        },
+    );
+
+    draw_text_ex(hi_score, 620.0, 545.0, 
+        TextParams {
+            font,
+            font_size: 25,
+            color: ORANGE,
+            ..Default::default()
+        },
+    );
+}
+
+fn show_text(font: Font, header_text: &str, message_text: &str) {
+    let header_dims = measure_text(header_text, Some(font), 50, 1.0);
+    let message_dims = measure_text(message_text, Some(font), 20, 1.0);
+
+    draw_text_ex(
+        header_text,
+        world::FIELD_WIDTH * 0.5 - header_dims.width * 0.5,
+        240.0,
+        TextParams {
+            font,
+            font_size: 50,
+            color: WHITE,
+            ..Default::default()
+        },
+    );
+
+    draw_text_ex(
+        message_text,
+        world::FIELD_WIDTH * 0.5 - message_dims.width * 0.5,
+        280.0,
+        TextParams {
+            font,
+            font_size: 20,
+            color: WHITE,
+            ..Default::default()
+        },
+    );
+}
+
+fn show_note(font: Font, note_text: &str) {
+    show_note_at(font, note_text, 320.0);
+}
+
+fn show_note_at(font: Font, note_text: &str, y: f32) {
+    let note_dims = measure_text(note_text, Some(font), 20, 1.0);
+
+    draw_text_ex(
+        note_text,
+        world::FIELD_WIDTH * 0.5 - note_dims.width * 0.5,
+        y,
+        TextParams {
+            font,
+            font_size: 20,
+            color: GRAY,
+            ..Default::default()
+        },
+    );
+}
+
+// position between the previous and the current tick
+fn lerp(prev: f32, cur: f32, alpha: f32) -> f32 {
+    prev + (cur - prev) * alpha
+}
+
+fn draw_hud(world: &World, resources: &Resources) {
+    // the running power-up with its seconds left, and the shield while it holds
+    let mut power_ups = String::new();
+    if let Some(power_up) = &world.power_up {
+        power_ups.push_str(format!("{} {:.1}  ", power_up.kind.info().name, power_up.ticks_left as f32 * world::TICK).as_str());
+    }
+    if world.player.shield {
+        power_ups.push_str(PowerUpKind::Shield.info().name);
+    }
+
+    draw_info(resources.font, 
+        world.game.score.to_string().as_str(), 
+        world.game.hi_score.to_string().as_str(),
+        world.game.lives.to_string().as_str(),
+        power_ups.as_str());
+}
+
+fn draw_enemies(world: &World, resources: &Resources, alpha: f32) {
+    for enemy in &world.enemies {
+        let texture = resources.sprites.frame(SpriteKey::enemy(enemy.kind), enemy.cur_frame);
+        draw_texture_ex(texture,
+            lerp(enemy.prev_x, enemy.x, alpha),
+            lerp(enemy.prev_y, enemy.y, alpha),
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(texture.width(), texture.height()) * enemy.scale),
+                ..Default::default()
+            });
+    }
+}
+
+// every standing bunker pixel shows its own part of the block texture
+fn draw_bunkers(world: &World, resources: &Resources) {
+    let tiles_x = (resources.block_texture.width() / BUNKER_PIXEL).max(1.0) as usize;
+    let tiles_y = (resources.block_texture.height() / BUNKER_PIXEL).max(1.0) as usize;
+
+    for bunker in &world.bunkers {
+        for row in 0..bunker.rows {
+            for column in 0..bunker.columns {
+                if !bunker.is_solid(column, row) {
+                    continue;
+                }
+                draw_texture_ex(resources.block_texture,
+                    bunker.x + column as f32 * BUNKER_PIXEL,
+                    bunker.y + row as f32 * BUNKER_PIXEL,
+                    WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(vec2(BUNKER_PIXEL, BUNKER_PIXEL)),
+                        source: Some(Rect::new(
+                            (column % tiles_x) as f32 * BUNKER_PIXEL,
+                            (row % tiles_y) as f32 * BUNKER_PIXEL,
+                            BUNKER_PIXEL,
+                            BUNKER_PIXEL)),
+                        ..Default::default()
+                    });
+            }
+        }
+    }
+}
+
+fn draw_bombs(world: &World, resources: &Resources, alpha: f32) {
+    for bomb in &world.bombs {
+        let texture = resources.sprites.frame(SpriteKey::bomb(bomb.kind), bomb.cur_frame);
+        let stats = bomb.kind.stats();
+        // a bomb that took a hit already looks worn
+        let tint = if bomb.hit_points < stats.hit_points { GRAY } else { BOMB_TINTS[bomb.kind as usize] };
+        draw_texture(texture,
+            lerp(bomb.prev_x, bomb.x, alpha),
+            lerp(bomb.prev_y, bomb.y, alpha),
+            tint);
+    }
+}
+
+fn draw_ufo(world: &World, resources: &Resources, alpha: f32) {
+    if let Some(ufo) = world.ufo.first() {
+        draw_texture(resources.sprites.frame(SpriteKey::Ufo, ufo.cur_frame),
+            lerp(ufo.prev_x, ufo.x, alpha),
+            lerp(ufo.prev_y, ufo.y, alpha),
+            WHITE);
+    }
+}
+
+fn draw_capsules(world: &World, resources: &Resources, alpha: f32) {
+    for capsule in &world.capsules {
+        let info = capsule.kind.info();
+        let x = lerp(capsule.prev_x, capsule.x, alpha);
+        let y = lerp(capsule.prev_y, capsule.y, alpha);
+        draw_rectangle(x, y, powerup::CAPSULE_SIZE, powerup::CAPSULE_SIZE, POWER_UP_COLORS[capsule.kind as usize]);
+
+        let dims = measure_text(info.letter, Some(resources.font), 18, 1.0);
+        draw_text_ex(
+            info.letter,
+            x + (powerup::CAPSULE_SIZE - dims.width) * 0.5,
+            y + (powerup::CAPSULE_SIZE + dims.height) * 0.5,
+            TextParams {
+                font: resources.font,
+                font_size: 18,
+                color: BLACK,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+fn draw_player(world: &World, resources: &Resources, alpha: f32) {
+    let player = &world.player;
+    if player.visible() {
+        let x = lerp(player.prev_x, player.x, alpha);
+        let y = lerp(player.prev_y, player.y, alpha);
+        draw_texture(resources.player_texture, x, y, WHITE);
+
+        if player.shield {
+            draw_rectangle_lines(x - 4.0, y - 4.0, player.rect.w + 8.0, player.rect.h + 8.0, 2.0,
+                POWER_UP_COLORS[PowerUpKind::Shield as usize]);
+        }
+    }
+
+    for bullet in &world.bullets {
+        draw_texture(resources.bullet_texture,
+            lerp(bullet.prev_x, bullet.x, alpha),
+            lerp(bullet.prev_y, bullet.y, alpha),
+            WHITE);
+    }
+}
+
+fn draw_effects(world: &World, resources: &Resources, alpha: f32) {
+    for effect in &world.effects {
+        let preset = effect.kind.preset();
+        let color = Color { a: effect.fade(), ..EFFECT_COLORS[effect.kind as usize] };
+
+        for particle in &effect.particles {
+            draw_rectangle(
+                lerp(particle.prev_x, particle.x, alpha) - preset.size * 0.5,
+                lerp(particle.prev_y, particle.y, alpha) - preset.size * 0.5,
+                preset.size,
+                preset.size,
+                color);
+        }
+
+        if let Some(score) = effect.score {
+            let text = score.to_string();
+            let dims = measure_text(text.as_str(), Some(resources.font), 25, 1.0);
+            draw_text_ex(
+                text.as_str(),
+                effect.x - dims.width * 0.5,
+                lerp(effect.prev_y, effect.y, alpha) + dims.height * 0.5,
+                TextParams {
+                    font: resources.font,
+                    font_size: 25,
+                    color: Color { a: effect.fade(), ..WHITE },
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+fn draw_high_scores(resources: &Resources, high_scores: &HighScores) {
+    draw_score_table(resources, high_scores, 100.0, 160.0, 30.0);
+}
+
+// header and entry baselines, so the table also fits above the main menu
+fn draw_score_table(resources: &Resources, high_scores: &HighScores, header_y: f32, top: f32, line_height: f32) {
+    let header_dims = measure_text("HIGH SCORES", Some(resources.font), 50, 1.0);
+    draw_text_ex(
+        "HIGH SCORES",
+        world::FIELD_WIDTH * 0.5 - header_dims.width * 0.5,
+        header_y,
+        TextParams {
+            font: resources.font,
+            font_size: 50,
+            color: ORANGE,
+            ..Default::default()
+        },
+    );
+
+    for (i, entry) in high_scores.entries.iter().enumerate() {
+        let line = format!("{:>2}. {}  {:>6}  M{:<3} {:<8} {}",
+            i + 1, entry.initials, entry.score, entry.mission, entry.mode, entry.date);
+        draw_text_ex(
+            line.as_str(),
+            60.0,
+            top + i as f32 * line_height,
+            TextParams {
+                font: resources.font,
+                font_size: 20,
+                color: WHITE,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+pub fn draw_initials_entry(resources: &Resources, entry: &InitialsEntry, score: i32, controls: &Bindings) {
+    show_text(resources.font, "NEW HIGH SCORE", format!("{} points - enter your initials", score).as_str());
+
+    for (i, letter) in entry.letters.iter().enumerate() {
+        let color = if i == entry.pos { ORANGE } else { WHITE };
+        draw_text_ex(
+            (*letter as char).to_string().as_str(),
+            world::FIELD_WIDTH * 0.5 - 60.0 + i as f32 * 45.0,
+            380.0,
+            TextParams {
+                font: resources.font,
+                font_size: 50,
+                color,
+                ..Default::default()
+            },
+        );
+    }
+
+    show_note(resources.font, format!("{}/{} to pick a letter, {} to confirm",
+        controls.key_name(Action::MoveLeft), controls.key_name(Action::MoveRight), controls.key_name(Action::Confirm)).as_str());
+}
+
+// lists the assets replaced by placeholders, shown once before the intro
+pub fn draw_asset_errors(resources: &Resources, controls: &Bindings) {
+    show_text(resources.font, "ASSET ERRORS", format!("press '{}' to play anyway...", controls.key_name(Action::Confirm)).as_str());
+
+    for (i, err) in resources.errors.iter().enumerate() {
+        draw_text_ex(
+            err.to_string().as_str(),
+            20.0,
+            320.0 + i as f32 * 20.0,
+            TextParams {
+                font: resources.font,
+                font_size: 15,
+                color: ORANGE,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+pub fn draw_menu(resources: &Resources, menu: &Menu, settings: &Settings, high_scores: &HighScores) {
+    let screen = menu.screen();
+    let top = match screen {
+        Screen::Main | Screen::MissionStart | Screen::MissionCompleted | Screen::LifeLost => 360.0,
+        Screen::Pause | Screen::GameOver => 340.0,
+        // the screens opened from a menu cover the game
+        _ => {
+            clear_background(BLACK);
+            200.0
+        },
+    };
+
+    match screen {
+        // like an arcade attract mode, the title and the high scores take turns above the menu
+        Screen::Main if !high_scores.entries.is_empty() && (get_time() / 5.0) as i64 % 2 == 1 => {
+            draw_rectangle(0.0, 0.0, world::FIELD_WIDTH, INTRO_HEIGHT, BLACK);
+            draw_score_table(resources, high_scores, 60.0, 100.0, 22.0);
+        },
+        Screen::HighScores => draw_high_scores(resources, high_scores),
+        Screen::Options | Screen::Controls => {
+            let header_dims = measure_text(screen.title(), Some(resources.font), 50, 1.0);
+            draw_text_ex(
+                screen.title(),
+                world::FIELD_WIDTH * 0.5 - header_dims.width * 0.5,
+                100.0,
+                TextParams {
+                    font: resources.font,
+                    font_size: 50,
+                    color: ORANGE,
+                    ..Default::default()
+                },
+            );
+        },
+        _ => {},
+    }
+
+    if screen == Screen::Controls && menu.binding.is_some() {
+        show_note_at(resources.font, "backspace or delete: cancel", 480.0);
+    } else if screen == Screen::Controls {
+        show_note_at(resources.font, "enter: add a key   delete: remove one   m, - and =: sound", 480.0);
+    }
+    if screen.items().is_empty() {
+        show_note_at(resources.font, "press 'escape' to go back", 510.0);
+    }
+
+    for (i, item) in screen.items().iter().enumerate() {
+        let mut label = match (item, menu.binding) {
+            (Item::Bind(action), Some(binding)) if *action == binding => format!("{}: PRESS A KEY...", action.name().to_uppercase()),
+            _ => item.label(settings),
+        };
+        let color = if i == menu.selected {
+            label = format!("> {} <", label);
+            ORANGE
+        } else {
+            WHITE
+        };
+        let dims = measure_text(label.as_str(), Some(resources.font), 25, 1.0);
+        draw_text_ex(
+            label.as_str(),
+            world::FIELD_WIDTH * 0.5 - dims.width * 0.5,
+            top + i as f32 * 35.0,
+            TextParams {
+                font: resources.font,
+                font_size: 25,
+                color,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+pub fn draw_world(world: &World, resources: &Resources, controls: &Bindings) {
+    let alpha = world.alpha();
+
+    match world.state {
+        GameState::Intro => {
+            // the menu takes the place of the old prompt
+            draw_texture_ex(resources.intro, 0.0, 0.0, WHITE,
+                DrawTextureParams {
+                    source: Some(Rect::new(0.0, 0.0, resources.intro.width(), INTRO_HEIGHT)),
+                    ..Default::default()
+                },
+            );
+        },
+        GameState::InitLevel => {
+            draw_hud(world, resources);
+            draw_enemies(world, resources, alpha);
+            draw_bunkers(world, resources);
+
+            let mut header_text = String::from("MISSION - ");
+            header_text.push_str(&world.game.mission.to_string());
+            show_text(resources.font, header_text.as_str(), format!("press '{}' to start...", controls.key_name(Action::Confirm)).as_str());
+            show_note(resources.font, world.level().name.as_str());
+        },
+        GameState::Game => {
+            draw_hud(world, resources);
+            draw_bombs(world, resources, alpha);
+            draw_ufo(world, resources, alpha);
+            draw_capsules(world, resources, alpha);
+            draw_player(world, resources, alpha);
+            draw_enemies(world, resources, alpha);
+            draw_bunkers(world, resources);
+            draw_effects(world, resources, alpha);
+        },
+        // the ship is gone and everything else stands still while it explodes
+        GameState::LevelFail => {
+            draw_hud(world, resources);
+            draw_bombs(world, resources, alpha);
+            draw_ufo(world, resources, alpha);
+            draw_capsules(world, resources, alpha);
+            draw_enemies(world, resources, alpha);
+            draw_bunkers(world, resources);
+            draw_effects(world, resources, alpha);
+        },
+        GameState::Paused => {
+            show_text(resources.font, "GAME PAUSED", "");
+        },
+        GameState::LevelCompleted => {
+            draw_hud(world, resources);
+            draw_bunkers(world, resources);
+            draw_effects(world, resources, alpha);
+            show_text(resources.font, "MISSION COMPLETED", format!("press '{}' to continue...", controls.key_name(Action::Confirm)).as_str());
+        },
+        GameState::GameOver => {
+            draw_hud(world, resources);
+            draw_enemies(world, resources, alpha);
+            draw_bunkers(world, resources);
+            draw_effects(world, resources, alpha);
+            show_text(resources.font, "GAME OVER", format!("seed: {}", world.seed).as_str());
+        },
+    }
+}
@@ -1,28 +1,47 @@
 use macroquad::prelude::*;
 
-// window size in pixels
-pub const WINDOW_WIDTH: i32 = 700;
-pub const WINDOW_HEIGHT: i32 = 550;
-pub const MINIMAL_TIME_BETWEEN_BOMBS: f64 = 0.6;
-pub const MINIMAL_TIME_BETWEEN_EACH_UFO: f64 = 7.0;
-pub const ENEMY_INIT_SPEED: f32 = 0.4;
+use sim::world;
+
+use crate::assets::{self, AssetError, Assets};
+use crate::sprites::Sprites;
+
+// starting window size in pixels, one pixel per playfield pixel
+pub const WINDOW_WIDTH: i32 = world::FIELD_WIDTH as i32;
+pub const WINDOW_HEIGHT: i32 = world::FIELD_HEIGHT as i32;
 
 pub struct Resources {
     pub player_texture: Texture2D,
     pub block_texture: Texture2D,
     pub bullet_texture: Texture2D,
+    pub sprites: Sprites,
     pub font: Font,
     pub intro: Texture2D,
+    // everything that failed to load and was replaced by a fallback
+    pub errors: Vec<AssetError>,
 }
 
 impl Resources {
-    pub async fn new() -> Self {
+    pub fn new(assets: &Assets) -> Self {
+        let mut errors: Vec<AssetError> = Vec::new();
+
+        let player_texture = assets::texture_or_placeholder(assets, "images/player.png", 70, 30, &mut errors);
+        let block_texture = assets::texture_or_placeholder(assets, "images/block.png", 10, 10, &mut errors);
+        let bullet_texture = assets::texture_or_placeholder(assets, "images/bullet.png", 6, 11, &mut errors);
+        let sprites = Sprites::load(assets, &mut errors);
+        let font = assets::font(assets, "fonts/game_font.ttf").unwrap_or_else(|err| {
+            errors.push(err);
+            Font::default()
+        });
+        let intro = assets::texture_or_placeholder(assets, "images/intro.png", WINDOW_WIDTH as u16, WINDOW_HEIGHT as u16, &mut errors);
+
         Self {
-            player_texture: load_texture("assets/images/player.png").await.unwrap(),
-            block_texture: load_texture("assets/images/block.png").await.unwrap(),
-            bullet_texture: load_texture("assets/images/bullet.png").await.unwrap(),
-            font: load_ttf_font("assets/fonts/game_font.ttf").await.unwrap(),
-            intro: load_texture("assets/images/intro.png").await.unwrap(),
+            player_texture,
+            block_texture,
+            bullet_texture,
+            sprites,
+            font,
+            intro,
+            errors,
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,145 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+use sim::game::{Difficulty, GameMode};
+
+use crate::input::Bindings;
+
+const FILE_NAME: &str = "settings.toml";
+
+// choices made in the menus, kept in the user's config folder and read at startup
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub muted: bool,
+    pub fullscreen: bool,
+    // whole multiples of the playfield size only, with wider black bars
+    pub integer_scaling: bool,
+    pub controls: Bindings,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::Classic,
+            difficulty: Difficulty::Normal,
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            muted: false,
+            fullscreen: false,
+            integer_scaling: false,
+            controls: Bindings::default(),
+            path: None,
+        }
+    }
+}
+
+impl Settings {
+    // a missing file means defaults, a broken setting is reported and keeps its default
+    pub fn load() -> Self {
+        let path = dirs::config_dir().map(|dir| dir.join("space-invaders").join(FILE_NAME));
+        let mut settings = match path.as_ref().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(content) => Settings::parse(&content),
+            None => Settings::default(),
+        };
+        settings.path = path;
+        settings
+    }
+
+    // every setting is taken on its own, so one bad value doesn't throw away the rest of the file
+    fn parse(content: &str) -> Self {
+        let file: toml::Table = match content.parse() {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("can't read settings: {}", err);
+                return Settings::default();
+            },
+        };
+
+        let mut settings = Settings::default();
+        let mut table = toml::Table::new();
+        for (name, value) in file {
+            table.insert(name.clone(), value);
+            match toml::Value::Table(table.clone()).try_into() {
+                Ok(parsed) => settings = parsed,
+                Err(err) => {
+                    eprintln!("can't read setting '{}': {}", name, err.message());
+                    table.remove(&name);
+                },
+            }
+        }
+        settings.controls.fill_defaults();
+        settings
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "no config folder")),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::KeyCode;
+
+    use super::*;
+    use crate::input::{Action, Key};
+
+    #[test]
+    fn broken_settings_keep_the_rest() {
+        let settings = Settings::parse(r#"
+            mode = "arcade"
+            difficulty = "impossible"
+            master_volume = "loud"
+            sfx_volume = 0.5
+
+            [controls]
+            move_left = ["a", "lfet"]
+            fire = ["spcae"]
+            pause = "p"
+            confirm = ["enter"]
+            jump = ["up"]
+        "#);
+
+        assert_eq!(settings.mode, GameMode::Arcade);
+        assert_eq!(settings.difficulty, Difficulty::Normal);
+        assert_eq!(settings.master_volume, 1.0);
+        assert_eq!(settings.sfx_volume, 0.5);
+
+        let defaults = Bindings::default();
+        assert_eq!(settings.controls.move_left, [Key(KeyCode::A)]);
+        assert_eq!(settings.controls.move_right, defaults.move_right);
+        assert_eq!(settings.controls.fire, defaults.fire);
+        assert_eq!(settings.controls.pause, defaults.pause);
+        assert_eq!(settings.controls.confirm, [Key(KeyCode::Enter)]);
+    }
+
+    #[test]
+    fn saved_settings_read_back() {
+        let mut settings = Settings { mode: GameMode::Arcade, muted: true, ..Settings::default() };
+        settings.controls.bind(Action::Fire, Key(KeyCode::Space));
+
+        let parsed = Settings::parse(&toml::to_string_pretty(&settings).unwrap());
+        assert_eq!(parsed.mode, GameMode::Arcade);
+        assert!(parsed.muted);
+        assert_eq!(parsed.controls, settings.controls);
+        assert_eq!(Settings::parse("not = [toml").controls, Bindings::default());
+    }
+}
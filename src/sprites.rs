@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use macroquad::prelude::*;
+
+use sim::bomb::BombKind;
+use sim::enemy::EnemyKind;
+
+use crate::assets::{self, AssetError, Assets};
+
+// every animated sprite of the game, loaded once at startup
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SpriteKey {
+    EnemyE,
+    EnemyA,
+    EnemyB,
+    BombA,
+    BombB,
+    Ufo,
+}
+
+impl SpriteKey {
+    pub const ALL: [SpriteKey; 6] = [
+        SpriteKey::EnemyE,
+        SpriteKey::EnemyA,
+        SpriteKey::EnemyB,
+        SpriteKey::BombA,
+        SpriteKey::BombB,
+        SpriteKey::Ufo,
+    ];
+
+    pub fn enemy(kind: EnemyKind) -> Self {
+        match kind {
+            EnemyKind::Squid => SpriteKey::EnemyE,
+            EnemyKind::Crab => SpriteKey::EnemyA,
+            EnemyKind::Octopus => SpriteKey::EnemyB,
+        }
+    }
+
+    pub fn bomb(kind: BombKind) -> Self {
+        match kind {
+            BombKind::Rolling | BombKind::Plunger => SpriteKey::BombA,
+            BombKind::Squiggly | BombKind::Splitter => SpriteKey::BombB,
+        }
+    }
+
+    pub fn frame_size(self) -> (u16, u16) {
+        match self {
+            SpriteKey::EnemyE | SpriteKey::EnemyA | SpriteKey::EnemyB => (50, 36),
+            SpriteKey::BombA | SpriteKey::BombB => (8, 15),
+            SpriteKey::Ufo => (100, 20),
+        }
+    }
+
+    pub fn frame_paths(self) -> Vec<String> {
+        match self {
+            SpriteKey::EnemyE => (1..=2).map(|i| format!("images/enemy/e_{}.png", i)).collect(),
+            SpriteKey::EnemyA => (1..=2).map(|i| format!("images/enemy/a_{}.png", i)).collect(),
+            SpriteKey::EnemyB => (1..=2).map(|i| format!("images/enemy/b_{}.png", i)).collect(),
+            SpriteKey::BombA => (1..=2).map(|i| format!("images/bombs/a_{}_bomb.png", i)).collect(),
+            SpriteKey::BombB => (1..=2).map(|i| format!("images/bombs/b_{}_bomb.png", i)).collect(),
+            SpriteKey::Ufo => (0..=4).map(|i| format!("images/ufo/ufo_{}.png", i)).collect(),
+        }
+    }
+}
+
+pub struct Sprites {
+    animations: HashMap<SpriteKey, Vec<Texture2D>>,
+}
+
+impl Sprites {
+    // frames that fail to load are replaced with placeholders and reported in errors
+    pub fn load(assets: &Assets, errors: &mut Vec<AssetError>) -> Self {
+        let mut animations: HashMap<SpriteKey, Vec<Texture2D>> = HashMap::new();
+
+        for key in SpriteKey::ALL {
+            let (width, height) = key.frame_size();
+            let frames = key.frame_paths()
+                .iter()
+                .map(|path| assets::texture_or_placeholder(assets, path, width, height, errors))
+                .collect();
+            animations.insert(key, frames);
+        }
+
+        Self {
+            animations,
+        }
+    }
+
+    pub fn frame(&self, key: SpriteKey, frame: usize) -> Texture2D {
+        self.animations[&key][frame]
+    }
+}